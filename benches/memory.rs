@@ -0,0 +1,92 @@
+//! Compares the peak heap usage of `Verifier`, `OneShotVerifier` and
+//! `ZeroCopyVerifier`.
+//!
+//! Run with `cargo bench --bench memory -- <file.mmb>...`.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+use trivial_verifier::{OneShotVerifier, Verifier, ZeroCopyVerifier};
+
+struct Counting;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() {
+            let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(current, Ordering::Relaxed);
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn reset() -> usize {
+    let current = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(current, Ordering::Relaxed);
+    current
+}
+
+fn measure<F: FnOnce() -> bool>(name: &str, f: F) {
+    let base = reset();
+    let start = Instant::now();
+
+    let ok = f();
+
+    let elapsed = start.elapsed();
+    let peak = PEAK.load(Ordering::Relaxed) - base;
+
+    println!(
+        "  {:<16} {:>12} bytes peak  {:>10.3?}  {}",
+        name,
+        peak,
+        elapsed,
+        if ok { "ok" } else { "FAILED" }
+    );
+}
+
+fn main() {
+    let files: Vec<String> = std::env::args()
+        .skip(1)
+        .filter(|x| !x.starts_with("--"))
+        .collect();
+
+    if files.is_empty() {
+        println!("usage: cargo bench --bench memory -- <file.mmb>...");
+        return;
+    }
+
+    for file in files {
+        let data = std::fs::read(&file).expect("unable to read file");
+
+        println!("{} ({} bytes)", file, data.len());
+
+        measure("Verifier", || match Verifier::new(&data) {
+            Some(mut verifier) => verifier.run(&mut |_, _| {}).is_ok(),
+            None => false,
+        });
+
+        measure("OneShotVerifier", || match OneShotVerifier::new(&data) {
+            Some(mut verifier) => verifier.run(&mut |_, _| {}).is_ok(),
+            None => false,
+        });
+
+        measure("ZeroCopyVerifier", || match ZeroCopyVerifier::new(&data) {
+            Some(mut verifier) => verifier.run(&mut |_, _| {}).is_ok(),
+            None => false,
+        });
+    }
+}
@@ -0,0 +1,64 @@
+use std::path::{Path, PathBuf};
+
+/// Finds the version of `trivial-kernel` that cargo resolved, in the lock
+/// file next to the target directory, which is the one of the workspace
+/// that builds this crate, or else in the lock file of the package.
+fn kernel_version() -> (Option<PathBuf>, String) {
+    let dirs = ["OUT_DIR", "CARGO_MANIFEST_DIR"]
+        .iter()
+        .filter_map(std::env::var_os)
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+
+    for lock in dirs
+        .iter()
+        .flat_map(|x| x.ancestors())
+        .map(|x| x.join("Cargo.lock"))
+    {
+        if let Some(version) = std::fs::read_to_string(&lock)
+            .ok()
+            .and_then(|x| find_version(&x, "trivial-kernel"))
+        {
+            return (Some(lock), version);
+        }
+    }
+
+    (None, "unknown".to_string())
+}
+
+fn find_version(lock: &str, name: &str) -> Option<String> {
+    let package = format!("name = \"{}\"", name);
+    let mut lines = lock.lines().skip_while(|x| *x != package).skip(1);
+
+    let version = lines.next()?.strip_prefix("version = \"")?;
+    Some(version.strip_suffix('"')?.to_string())
+}
+
+fn main() {
+    let (lock, version) = kernel_version();
+
+    println!("cargo:rustc-env=TRIVIAL_KERNEL_VERSION={}", version);
+
+    if let Some(lock) = lock.as_deref().and_then(Path::to_str) {
+        println!("cargo:rerun-if-changed={}", lock);
+    }
+
+    println!("cargo:rerun-if-changed=build.rs");
+
+    #[cfg(feature = "ffi")]
+    {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
+        let config = cbindgen::Config::from_file(format!("{}/cbindgen.toml", dir))
+            .expect("the configuration can be read");
+
+        cbindgen::Builder::new()
+            .with_config(config)
+            .with_src(format!("{}/src/ffi.rs", dir))
+            .generate()
+            .expect("the header can be generated")
+            .write_to_file(format!("{}/include/trivial_verifier.h", dir));
+
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+    }
+}
@@ -0,0 +1,212 @@
+#ifndef TRIVIAL_VERIFIER_H
+#define TRIVIAL_VERIFIER_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The outcome of a call, `TRIVIAL_STATUS_OK` on success and the error kind
+ * of the kernel if verification failed.
+ */
+typedef enum TrivialStatus {
+  TRIVIAL_STATUS_OK = 0,
+  /**
+   * There was nothing left to verify.
+   */
+  TRIVIAL_STATUS_FINISHED = 1,
+  /**
+   * The buffer is not a valid file.
+   */
+  TRIVIAL_STATUS_INVALID_FILE = 2,
+  /**
+   * A pointer argument was null or a string was not valid UTF-8.
+   */
+  TRIVIAL_STATUS_INVALID_ARGUMENT = 3,
+  /**
+   * The verifier panicked, after which the handle can only be freed.
+   */
+  TRIVIAL_STATUS_PANICKED = 4,
+  TRIVIAL_STATUS_INVALID_HEAP_INDEX = 16,
+  TRIVIAL_STATUS_INVALID_PROOF_INDEX = 17,
+  TRIVIAL_STATUS_INVALID_THEOREM = 18,
+  TRIVIAL_STATUS_INVALID_STORE_INDEX = 19,
+  TRIVIAL_STATUS_INVALID_STORE_TYPE = 20,
+  TRIVIAL_STATUS_INVALID_TERM = 21,
+  TRIVIAL_STATUS_INVALID_STORE_EXPR = 22,
+  TRIVIAL_STATUS_INVALID_SORT = 23,
+  TRIVIAL_STATUS_INVALID_BINDER_INDICES = 24,
+  TRIVIAL_STATUS_INVALID_UNIFY_COMMAND_INDEX = 25,
+  TRIVIAL_STATUS_INVALID_STACK_TYPE = 26,
+  TRIVIAL_STATUS_INCOMPATIBLE_TYPES = 27,
+  TRIVIAL_STATUS_DEPENDENCY_OVERFLOW = 28,
+  TRIVIAL_STATUS_UNIFY_STACK_UNDERFLOW = 29,
+  TRIVIAL_STATUS_CANT_SAVE_CONVERTABILITY_OBLIGATION = 30,
+  TRIVIAL_STATUS_UNIFY_REF_FAILURE = 31,
+  TRIVIAL_STATUS_UNIFY_TERM_FAILURE = 32,
+  TRIVIAL_STATUS_PROOF_STACK_UNDERFLOW = 33,
+  TRIVIAL_STATUS_SORT_NOT_PROVABLE = 34,
+  TRIVIAL_STATUS_SORT_IS_STRICT = 35,
+  TRIVIAL_STATUS_SORT_IS_PURE = 36,
+  TRIVIAL_STATUS_STACK_HAS_MORE_THAN_ONE = 37,
+  TRIVIAL_STATUS_UNACCOUNTED_DEPENDENCIES = 38,
+  TRIVIAL_STATUS_BAD_RETURN_TYPE = 39,
+  TRIVIAL_STATUS_TYPE_ERROR = 40,
+  TRIVIAL_STATUS_TOO_MANY_BOUND_VARIABLES = 41,
+  TRIVIAL_STATUS_HYP_STACK_UNDERFLOW = 42,
+  TRIVIAL_STATUS_DUMMY_COMMAND_IN_THEOREM = 43,
+  TRIVIAL_STATUS_CONG_UNIFY_ERROR = 44,
+  TRIVIAL_STATUS_BIND_DEP = 45,
+  TRIVIAL_STATUS_DISJOINT_VARIABLE_VIOLATION = 46,
+  TRIVIAL_STATUS_UNKNOWN_COMMAND = 47,
+  TRIVIAL_STATUS_UNFINISHED_HYP_STACK = 48,
+  TRIVIAL_STATUS_UNFINISHED_UNIFY_STACK = 49,
+  TRIVIAL_STATUS_HYP_IN_DEF_STATEMENT = 50,
+  TRIVIAL_STATUS_INVALID_OPCODE_IN_DEF = 51,
+  TRIVIAL_STATUS_IMPOSSIBLE = 52,
+  TRIVIAL_STATUS_THEOREM_OUT_OF_RANGE = 53,
+  TRIVIAL_STATUS_TERM_OUT_OF_RANGE = 54,
+  TRIVIAL_STATUS_SORT_OUT_OF_RANGE = 55,
+  TRIVIAL_STATUS_STREAM_EXHAUSTED = 56,
+  TRIVIAL_STATUS_MISSING_PROOF_STREAM = 57,
+} TrivialStatus;
+
+/**
+ * An opaque handle to a verifier.
+ */
+typedef struct TrivialVerifier TrivialVerifier;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the name of a status as a static string.
+ */
+const char *trivial_status_message(enum TrivialStatus status);
+
+/**
+ * Creates a verifier from a copy of `len` bytes at `data`, or returns null
+ * if they are not a valid file. A file without an index has no names.
+ *
+ * # Safety
+ *
+ * `data` has to point to `len` readable bytes.
+ */
+struct TrivialVerifier *trivial_verifier_new(const uint8_t *data, size_t len);
+
+/**
+ * Releases a verifier, null is ignored.
+ *
+ * # Safety
+ *
+ * `verifier` has to be null or returned by `trivial_verifier_new`, and is
+ * invalid afterwards.
+ */
+void trivial_verifier_free(struct TrivialVerifier *verifier);
+
+/**
+ * Verifies the remaining statements.
+ *
+ * # Safety
+ *
+ * `verifier` has to be null or a valid handle.
+ */
+enum TrivialStatus trivial_verifier_run(struct TrivialVerifier *verifier);
+
+/**
+ * Takes one step, which returns `TRIVIAL_STATUS_FINISHED` if there was
+ * nothing left to verify.
+ *
+ * # Safety
+ *
+ * `verifier` has to be null or a valid handle.
+ */
+enum TrivialStatus trivial_verifier_step(struct TrivialVerifier *verifier);
+
+/**
+ * The number of sorts, terms and theorems of the file.
+ *
+ * # Safety
+ *
+ * `verifier` has to be a valid handle, and the pointers null or writable.
+ */
+enum TrivialStatus trivial_verifier_counts(const struct TrivialVerifier *verifier,
+                                           size_t *sorts,
+                                           size_t *terms,
+                                           size_t *theorems);
+
+/**
+ * Writes the name of sort `idx` to `buf`, see `trivial_verifier_theorem_name`.
+ *
+ * # Safety
+ *
+ * `verifier` has to be a valid handle, and `buf` null or `len` writable bytes.
+ */
+ptrdiff_t trivial_verifier_sort_name(const struct TrivialVerifier *verifier,
+                                     size_t idx,
+                                     char *buf,
+                                     size_t len);
+
+/**
+ * Writes the name of term `idx` to `buf`, see `trivial_verifier_theorem_name`.
+ *
+ * # Safety
+ *
+ * `verifier` has to be a valid handle, and `buf` null or `len` writable bytes.
+ */
+ptrdiff_t trivial_verifier_term_name(const struct TrivialVerifier *verifier,
+                                     size_t idx,
+                                     char *buf,
+                                     size_t len);
+
+/**
+ * Writes the NUL terminated name of theorem `idx` to `buf`, truncated to
+ * `len` bytes. Returns the length of the whole name, or -1 if there is no
+ * such theorem or it has no name.
+ *
+ * # Safety
+ *
+ * `verifier` has to be a valid handle, and `buf` null or `len` writable bytes.
+ */
+ptrdiff_t trivial_verifier_theorem_name(const struct TrivialVerifier *verifier,
+                                        size_t idx,
+                                        char *buf,
+                                        size_t len);
+
+/**
+ * Returns the index of the sort `name`, or -1 if there is none.
+ *
+ * # Safety
+ *
+ * `verifier` has to be a valid handle, and `name` a NUL terminated string.
+ */
+ptrdiff_t trivial_verifier_sort_index(const struct TrivialVerifier *verifier, const char *name);
+
+/**
+ * Returns the index of the term `name`, or -1 if there is none.
+ *
+ * # Safety
+ *
+ * `verifier` has to be a valid handle, and `name` a NUL terminated string.
+ */
+ptrdiff_t trivial_verifier_term_index(const struct TrivialVerifier *verifier, const char *name);
+
+/**
+ * Returns the index of the theorem `name`, or -1 if there is none.
+ *
+ * # Safety
+ *
+ * `verifier` has to be a valid handle, and `name` a NUL terminated string.
+ */
+ptrdiff_t trivial_verifier_theorem_index(const struct TrivialVerifier *verifier, const char *name);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* TRIVIAL_VERIFIER_H */
@@ -0,0 +1,606 @@
+use std::process::exit;
+use trivial_verifier::cache::{self, Cache, Mode};
+use trivial_verifier::certificate::Certificate;
+use trivial_verifier::kernel::{opcode, Table, Term};
+use trivial_verifier::lsp::LanguageServer;
+use trivial_verifier::mm0::{self, Mm0, Printer};
+use trivial_verifier::search::{self, Pattern, Query};
+use trivial_verifier::server;
+use trivial_verifier::trace;
+use trivial_verifier::{OneShotVerifier, Verifier, ZeroCopyVerifier};
+
+const USAGE: &str = "usage: trivial-verify <command> <file.mmb> [options]
+
+commands:
+    verify <file.mmb> [--one-shot | --zero-copy]
+        verify every statement in the file, with --one-shot the proofs are
+        released as soon as they are checked, with --zero-copy they are
+        decoded from the file as they are checked
+
+    verify <file.mmb> --cache <file> [--trust-cache]
+        verify every statement in the file and record the verified
+        statements in the cache, with --trust-cache statements that are
+        in the cache are not verified again
+
+    statements <file.mmb> [--public]
+        list the statements of the file in order, with --public only those
+        that are exported
+
+    search <file.mmb> [--pattern <sexpr>] [--mentions <term>]... [--name <regex>]
+           [--mm0 <file.mm0>]
+        list the theorems matching all given filters, best matches first,
+        with --mm0 the statements are printed in the notation of the file
+
+    check <file.mmb> <file.mm0>
+        verify the file and check that it exports exactly the statements of
+        the specification
+
+    export <file.mmb>
+        print the exported statements of the file as an mm0 specification
+
+    dump <file.mmb>
+        print every sort, term and theorem of the file as versioned json
+
+    docs <file.mmb> <dir> [--mm0 <file.mm0>]
+        write html pages for every sort, term and theorem of the file into
+        the directory, with --mm0 the statements are printed in the notation
+        of the file
+
+    proof <file.mmb> <theorem> [--mm0 <file.mm0>]
+        list the steps of the proof of the theorem, with the steps each one
+        uses and the entry it leaves on the proof stack, with --mm0 the
+        expressions are printed in the notation of the file
+
+    extract <file.mmb> <theorem> <out.mmb>
+        write a file with only the theorem and everything its proof needs
+
+    diff <old.mmb> <new.mmb>
+        list the sorts, terms and theorems that were added, removed, renamed
+        or changed, matched by name
+
+    semver <old.mmb> <new.mmb>
+        fail if the new file removes or changes a public sort, term or
+        theorem, proof changes and additions are allowed
+
+    certify <file.mmb>
+        verify every statement in the file and print a certificate as json
+
+    check-certificate <file.mmb> <certificate.json>
+        check that the certificate was made for the file by this version
+        of the verifier and the kernel, without verifying the proofs
+
+    serve
+        answer json-rpc requests on stdin, see the documentation of the
+        server module
+
+    lsp
+        run a language server on stdin for mm0 files, which verifies the mmb
+        file of the same name and reports the proofs that fail
+
+    repl <file.mmb>
+        explore the file interactively, needs the repl feature
+
+    tui <file.mmb>
+        step through the proofs of the file in a full-screen view, needs the
+        tui feature";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("error: {}", msg);
+    exit(1)
+}
+
+fn load(path: &str) -> (Vec<u8>, Verifier) {
+    let data = std::fs::read(path).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+    let verifier =
+        Verifier::new(&data).unwrap_or_else(|| fail(&format!("{}: not a valid mmb file", path)));
+
+    (data, verifier)
+}
+
+fn load_mm0(path: &str) -> (String, Mm0) {
+    let text = std::fs::read_to_string(path).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+    let mm0 = Mm0::parse(&text).unwrap_or_else(|e| {
+        let (line, col) = mm0::position(&text, e.offset);
+        fail(&format!("{}:{}:{}: {:?}", path, line + 1, col + 1, e.kind))
+    });
+
+    (text, mm0)
+}
+
+fn verify(args: &[String]) {
+    let result = match args {
+        [path] => load(path).1.run(&mut |_, _| {}),
+        [path, flag] if flag == "--one-shot" => {
+            let data = std::fs::read(path).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+            let mut verifier = OneShotVerifier::new(&data)
+                .unwrap_or_else(|| fail(&format!("{}: not a valid mmb file", path)));
+
+            verifier.run(&mut |_, _| {})
+        }
+        [path, flag] if flag == "--zero-copy" => {
+            let data = std::fs::read(path).unwrap_or_else(|e| fail(&format!("{}: {}", path, e)));
+            let mut verifier = ZeroCopyVerifier::new(&data)
+                .unwrap_or_else(|| fail(&format!("{}: not a valid mmb file", path)));
+
+            verifier.run(&mut |_, _| {})
+        }
+        [path, flag, cache] if flag == "--cache" => {
+            return verify_cached(path, cache, Mode::FullCheck);
+        }
+        [path, flag, cache, trust] if flag == "--cache" && trust == "--trust-cache" => {
+            return verify_cached(path, cache, Mode::TrustCache);
+        }
+        _ => fail(USAGE),
+    };
+
+    if let Err(e) = result {
+        fail(&format!("verification failed: {:?}", e));
+    }
+
+    println!("ok");
+}
+
+fn verify_cached(path: &str, cache_path: &str, mode: Mode) {
+    let (data, mut verifier) = load(path);
+
+    let hashes = cache::statement_hashes(&data)
+        .unwrap_or_else(|| fail(&format!("{}: not a valid mmb file", path)));
+
+    // a missing or outdated cache is started over
+    let mut cache = std::fs::read_to_string(cache_path)
+        .ok()
+        .and_then(|x| Cache::parse(&x))
+        .unwrap_or_default();
+
+    let skipped = cache::run(&mut verifier, &hashes, &mut cache, mode)
+        .unwrap_or_else(|e| fail(&format!("verification failed: {:?}", e)));
+
+    std::fs::write(cache_path, cache.to_string())
+        .unwrap_or_else(|e| fail(&format!("{}: {}", cache_path, e)));
+
+    println!("ok, {} of {} statements cached", skipped, hashes.len());
+}
+
+fn statements(args: &[String]) {
+    let (path, public) = match args {
+        [path] => (path, false),
+        [path, flag] if flag == "--public" => (path, true),
+        _ => fail(USAGE),
+    };
+
+    let (_, verifier) = load(path);
+
+    let statements = if public {
+        verifier.get_public_statements()
+    } else {
+        verifier.get_statements()
+    };
+
+    for i in statements {
+        let name = if i.is_sort() {
+            verifier.get_sort_name(i.index)
+        } else if i.is_term() {
+            verifier.get_term_name(i.index)
+        } else {
+            verifier.get_theorem_name(i.index)
+        };
+
+        let kind = match i.kind {
+            opcode::Statement::Sort => "sort",
+            opcode::Statement::TermDef => match verifier.table.get_term(i.index as u32) {
+                Some(term) if term.is_definition() => "def",
+                _ => "term",
+            },
+            opcode::Statement::LocalDef => "local def",
+            opcode::Statement::Axiom => "axiom",
+            opcode::Statement::Thm => "theorem",
+            opcode::Statement::LocalTerm => "local theorem",
+            opcode::Statement::End => continue,
+        };
+
+        println!("{} {}", kind, name.unwrap_or("<unnamed>"));
+    }
+}
+
+fn search(args: &[String]) {
+    let (path, args) = match args.split_first() {
+        Some(x) => x,
+        None => fail(USAGE),
+    };
+
+    let (_, verifier) = load(path);
+    let mut query = Query::default();
+    let mut spec = None;
+    let mut args = args.iter();
+
+    while let Some(flag) = args.next() {
+        let value = args.next().unwrap_or_else(|| fail(USAGE));
+
+        match flag.as_str() {
+            "--pattern" => {
+                let pattern = Pattern::parse(value, &verifier)
+                    .unwrap_or_else(|e| fail(&format!("invalid pattern: {:?}", e)));
+                query.pattern = Some(pattern);
+            }
+            "--mentions" => {
+                let id = verifier
+                    .get_term_index(value)
+                    .unwrap_or_else(|| fail(&format!("unknown term: {}", value)));
+                query.mentions.push(id as u32);
+            }
+            "--name" => {
+                let regex = regex::Regex::new(value)
+                    .unwrap_or_else(|e| fail(&format!("invalid regex: {}", e)));
+                query.name = Some(regex);
+            }
+            "--mm0" => spec = Some(load_mm0(value)),
+            _ => fail(USAGE),
+        }
+    }
+
+    let hits = search::search(&verifier, &query)
+        .unwrap_or_else(|e| fail(&format!("search failed: {:?}", e)));
+
+    let names = |id: u32| verifier.get_term_name(id as usize);
+
+    let printer = spec.as_ref().map(|(_, mm0)| {
+        Printer::new(&verifier, mm0).unwrap_or_else(|e| fail(&format!("invalid mm0 file: {:?}", e)))
+    });
+
+    for hit in hits {
+        let name = verifier
+            .get_theorem_name(hit.theorem as usize)
+            .unwrap_or("<unnamed>");
+
+        if let Some(printer) = &printer {
+            let (hyps, conclusion) = printer
+                .display_theorem(&verifier, hit.theorem)
+                .unwrap_or_else(|e| fail(&format!("{:?}", e)));
+
+            for i in hyps {
+                println!("{}: {} >", name, i);
+            }
+
+            println!("{}: {}", name, conclusion);
+            continue;
+        }
+
+        let (_, conclusion) = verifier
+            .get_theorem_statement(hit.theorem)
+            .unwrap_or_else(|e| fail(&format!("{:?}", e)));
+
+        println!("{}: {}", name, conclusion.to_display(names));
+    }
+}
+
+fn check(args: &[String]) {
+    let (path, spec) = match args {
+        [path, spec] => (path, spec),
+        _ => fail(USAGE),
+    };
+
+    let (_, mut verifier) = load(path);
+
+    let (text, mm0) = load_mm0(spec);
+
+    if let Err(e) = verifier.run(&mut |_, _| {}) {
+        fail(&format!("verification failed: {:?}", e));
+    }
+
+    if let Err(e) = mm0::check(&verifier, &mm0) {
+        match e.offset.map(|x| mm0::position(&text, x)) {
+            Some((line, col)) => fail(&format!("{}:{}:{}: {}", spec, line + 1, col + 1, e)),
+            None => fail(&format!("{}: {}", spec, e)),
+        }
+    }
+
+    println!("ok");
+}
+
+fn export(args: &[String]) {
+    let path = match args {
+        [path] => path,
+        _ => fail(USAGE),
+    };
+
+    let (_, verifier) = load(path);
+
+    let spec = mm0::export(&verifier).unwrap_or_else(|e| fail(&format!("export failed: {:?}", e)));
+
+    print!("{}", spec);
+}
+
+fn dump(args: &[String]) {
+    let path = match args {
+        [path] => path,
+        _ => fail(USAGE),
+    };
+
+    let (_, verifier) = load(path);
+
+    let dump = trivial_verifier::dump::dump(&verifier)
+        .unwrap_or_else(|e| fail(&format!("dump failed: {:?}", e)));
+
+    println!("{:#}", dump);
+}
+
+fn docs(args: &[String]) {
+    let (path, dir, spec) = match args {
+        [path, dir] => (path, dir, None),
+        [path, dir, flag, spec] if flag == "--mm0" => (path, dir, Some(spec)),
+        _ => fail(USAGE),
+    };
+
+    let (_, verifier) = load(path);
+    let mm0 = spec.map(|x| load_mm0(x).1);
+
+    let pages = trivial_verifier::docs::generate(&verifier, mm0.as_ref())
+        .unwrap_or_else(|e| fail(&format!("docs failed: {:?}", e)));
+
+    trivial_verifier::docs::write(dir.as_ref(), &pages)
+        .unwrap_or_else(|e| fail(&format!("{}: {}", dir, e)));
+
+    println!("wrote {} pages to {}", pages.len(), dir);
+}
+
+fn proof(args: &[String]) {
+    let (path, name, spec) = match args {
+        [path, name] => (path, name, None),
+        [path, name, flag, spec] if flag == "--mm0" => (path, name, Some(spec)),
+        _ => fail(USAGE),
+    };
+
+    let (_, mut verifier) = load(path);
+    let mm0 = spec.map(|x| load_mm0(x).1);
+
+    let id = verifier
+        .get_theorem_index(name)
+        .unwrap_or_else(|| fail(&format!("unknown theorem: {}", name)));
+
+    let trace = trace::trace(&mut verifier, id).unwrap_or_else(|| fail("no such theorem"));
+
+    let listing = match &mm0 {
+        Some(mm0) => {
+            let printer = Printer::new(&verifier, mm0)
+                .unwrap_or_else(|e| fail(&format!("invalid mm0 file: {:?}", e)));
+            let vars = printer.theorem_vars(&verifier, id as u32);
+
+            trace::listing(&verifier, &trace, |x| {
+                printer.to_display(x, &vars).to_string()
+            })
+        }
+        None => trace::listing(&verifier, &trace, |x| {
+            x.to_display(|id| verifier.get_term_name(id as usize))
+                .to_string()
+        }),
+    };
+
+    print!("{}", listing);
+
+    if trace.error.is_some() {
+        exit(1);
+    }
+}
+
+fn extract(args: &[String]) {
+    let (path, name, out) = match args {
+        [path, name, out] => (path, name, out),
+        _ => fail(USAGE),
+    };
+
+    let (_, verifier) = load(path);
+
+    let id = verifier
+        .get_theorem_index(name)
+        .unwrap_or_else(|| fail(&format!("unknown theorem: {}", name)));
+
+    let builder = trivial_verifier::extract::extract(&verifier, id as u32)
+        .unwrap_or_else(|| fail("extraction failed"));
+
+    std::fs::write(out, builder.to_bytes()).unwrap_or_else(|e| fail(&format!("{}: {}", out, e)));
+
+    println!(
+        "{} sorts, {} terms, {} theorems",
+        builder.sorts.len(),
+        builder.terms.len(),
+        builder.theorems.len()
+    );
+}
+
+fn diff(args: &[String]) {
+    let (old, new) = match args {
+        [old, new] => (old, new),
+        _ => fail(USAGE),
+    };
+
+    let (_, old) = load(old);
+    let (_, new) = load(new);
+
+    let diff = trivial_verifier::diff::diff(&old, &new)
+        .unwrap_or_else(|e| fail(&format!("diff failed: {:?}", e)));
+
+    for (kind, changes) in &[
+        ("sorts", &diff.sorts),
+        ("terms", &diff.terms),
+        ("theorems", &diff.theorems),
+    ] {
+        if changes.is_empty() {
+            continue;
+        }
+
+        println!("{}:", kind);
+
+        for i in changes.iter() {
+            println!("    {}", i);
+        }
+    }
+}
+
+fn semver(args: &[String]) {
+    let (old, new) = match args {
+        [old, new] => (old, new),
+        _ => fail(USAGE),
+    };
+
+    let (_, old) = load(old);
+    let (_, new) = load(new);
+
+    let diff = trivial_verifier::diff::diff(&old, &new)
+        .unwrap_or_else(|e| fail(&format!("diff failed: {:?}", e)));
+
+    let breaking: Vec<_> = diff.breaking().collect();
+
+    if breaking.is_empty() {
+        println!("ok");
+    } else {
+        for i in &breaking {
+            println!("{}", i);
+        }
+
+        fail(&format!("{} breaking changes", breaking.len()));
+    }
+}
+
+fn certify(args: &[String]) {
+    let path = match args {
+        [path] => path,
+        _ => fail(USAGE),
+    };
+
+    let (data, mut verifier) = load(path);
+
+    if let Err(e) = verifier.run(&mut |_, _| {}) {
+        fail(&format!("verification failed: {:?}", e));
+    }
+
+    let certificate = Certificate::new(&data, &verifier)
+        .unwrap_or_else(|e| fail(&format!("certificate failed: {:?}", e)));
+
+    println!("{:#}", certificate.to_json());
+}
+
+fn check_certificate(args: &[String]) {
+    let (path, cert) = match args {
+        [path, cert] => (path, cert),
+        _ => fail(USAGE),
+    };
+
+    let (data, verifier) = load(path);
+
+    let text = std::fs::read_to_string(cert).unwrap_or_else(|e| fail(&format!("{}: {}", cert, e)));
+    let expected = serde_json::from_str(&text)
+        .ok()
+        .and_then(|x| Certificate::from_json(&x))
+        .unwrap_or_else(|| fail(&format!("{}: not a valid certificate", cert)));
+
+    let actual = Certificate::new(&data, &verifier)
+        .unwrap_or_else(|e| fail(&format!("certificate failed: {:?}", e)));
+
+    let mismatches = expected.mismatches(&actual);
+
+    if !mismatches.is_empty() {
+        fail(&format!(
+            "certificate does not match: {}",
+            mismatches.join(", ")
+        ));
+    }
+
+    println!("ok");
+}
+
+fn serve(args: &[String]) {
+    if !args.is_empty() {
+        fail(USAGE);
+    }
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+
+    if let Err(e) = server::serve(stdin.lock(), stdout.lock()) {
+        fail(&e.to_string());
+    }
+}
+
+fn lsp(args: &[String]) {
+    if !args.is_empty() {
+        fail(USAGE);
+    }
+
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+    let mut server = LanguageServer::new();
+
+    if let Err(e) = server::run(&mut server, stdin.lock(), stdout.lock()) {
+        fail(&e.to_string());
+    }
+}
+
+#[cfg(feature = "repl")]
+fn repl(args: &[String]) {
+    use trivial_verifier::repl::{self, Repl};
+
+    let path = match args {
+        [path] => path,
+        _ => fail(USAGE),
+    };
+
+    let history = std::env::var_os("HOME")
+        .map(|x| std::path::PathBuf::from(x).join(".trivial_verify_history"));
+
+    let mut repl = Repl::new(load(path).1);
+
+    if let Err(e) = repl::run(&mut repl, history.as_deref()) {
+        fail(&e.to_string());
+    }
+}
+
+#[cfg(not(feature = "repl"))]
+fn repl(_: &[String]) {
+    fail("built without the repl feature");
+}
+
+#[cfg(feature = "tui")]
+fn tui(args: &[String]) {
+    use trivial_verifier::tui::{self, Explorer};
+
+    let path = match args {
+        [path] => path,
+        _ => fail(USAGE),
+    };
+
+    let mut explorer = Explorer::new(load(path).1);
+
+    if let Err(e) = tui::run(&mut explorer) {
+        fail(&e.to_string());
+    }
+}
+
+#[cfg(not(feature = "tui"))]
+fn tui(_: &[String]) {
+    fail("built without the tui feature");
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    match args.split_first() {
+        Some((cmd, rest)) if cmd == "verify" => verify(rest),
+        Some((cmd, rest)) if cmd == "statements" => statements(rest),
+        Some((cmd, rest)) if cmd == "search" => search(rest),
+        Some((cmd, rest)) if cmd == "check" => check(rest),
+        Some((cmd, rest)) if cmd == "export" => export(rest),
+        Some((cmd, rest)) if cmd == "dump" => dump(rest),
+        Some((cmd, rest)) if cmd == "docs" => docs(rest),
+        Some((cmd, rest)) if cmd == "proof" => proof(rest),
+        Some((cmd, rest)) if cmd == "extract" => extract(rest),
+        Some((cmd, rest)) if cmd == "diff" => diff(rest),
+        Some((cmd, rest)) if cmd == "semver" => semver(rest),
+        Some((cmd, rest)) if cmd == "certify" => certify(rest),
+        Some((cmd, rest)) if cmd == "check-certificate" => check_certificate(rest),
+        Some((cmd, rest)) if cmd == "serve" => serve(rest),
+        Some((cmd, rest)) if cmd == "lsp" => lsp(rest),
+        Some((cmd, rest)) if cmd == "repl" => repl(rest),
+        Some((cmd, rest)) if cmd == "tui" => tui(rest),
+        _ => fail(USAGE),
+    }
+}
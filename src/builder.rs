@@ -0,0 +1,412 @@
+use crate::kernel::opcode::{self, Command};
+use crate::kernel::{Sort_, Table, Term, Theorem, Var, Var_};
+use crate::Verifier;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// The version of the file format that is written.
+pub const VERSION: u8 = 1;
+
+/// The number of sorts a file can hold.
+pub const MAX_SORTS: usize = 128;
+
+const HEADER_SIZE: usize = 40;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TermEntry {
+    pub name: String,
+    /// The sort of the term, with `0x80` set for definitions.
+    pub sort: u8,
+    pub binders: Vec<Var_>,
+    pub ret: Var_,
+    /// The unify stream of a definition, without the end command.
+    pub unify: Vec<Command<opcode::Unify>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct TheoremEntry {
+    pub name: String,
+    pub binders: Vec<Var_>,
+    /// The unify stream, without the end command.
+    pub unify: Vec<Command<opcode::Unify>>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct StatementEntry {
+    pub code: opcode::Statement,
+    /// The proof stream without the end command, or `None` if the statement
+    /// has none.
+    pub proof: Option<Vec<Command<opcode::Proof>>>,
+}
+
+/// Builds an MMB file from sorts, terms, theorems and their proofs.
+///
+/// Every `add_*` function appends a declaration together with its statement
+/// and returns the index of the declaration. The streams are given without
+/// their end commands, these are added when the file is written.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct MmbBuilder {
+    pub sorts: Vec<(String, Sort_)>,
+    pub terms: Vec<TermEntry>,
+    pub theorems: Vec<TheoremEntry>,
+    pub statements: Vec<StatementEntry>,
+}
+
+impl MmbBuilder {
+    pub fn new() -> MmbBuilder {
+        MmbBuilder::default()
+    }
+
+    /// Copies every declaration and statement of a file.
+    ///
+    /// This must not be called while the verifier is in the middle of a
+    /// statement, as the proof streams are not available then.
+    pub fn from_verifier(verifier: &Verifier) -> Option<MmbBuilder> {
+        let table = &verifier.table;
+        let mut builder = MmbBuilder::new();
+
+        while let Some(sort) = table.get_sort(builder.sorts.len() as u8) {
+            let idx = builder.sorts.len();
+            let name = verifier.get_sort_name(idx).unwrap_or_default();
+
+            builder.sorts.push((name.to_string(), *sort));
+        }
+
+        while let Some(term) = table.get_term(builder.terms.len() as u32) {
+            let idx = builder.terms.len();
+            let name = verifier.get_term_name(idx).unwrap_or_default();
+
+            builder.terms.push(TermEntry {
+                name: name.to_string(),
+                sort: term.sort,
+                binders: table.get_binders(term.get_binders())?.to_vec(),
+                ret: *term.get_return_type(),
+                unify: strip_end(table.get_unify_commands(term.get_command_stream())?),
+            });
+        }
+
+        while let Some(thm) = table.get_theorem(builder.theorems.len() as u32) {
+            let idx = builder.theorems.len();
+            let name = verifier.get_theorem_name(idx).unwrap_or_default();
+
+            builder.theorems.push(TheoremEntry {
+                name: name.to_string(),
+                binders: table.get_binders(thm.get_binders())?.to_vec(),
+                unify: strip_end(table.get_unify_commands(thm.get_unify_commands())?),
+            });
+        }
+
+        for (idx, i) in verifier.get_statements().iter().enumerate() {
+            builder.statements.push(StatementEntry {
+                code: i.kind,
+                proof: verifier.get_proof(idx).map(|x| x.to_vec()),
+            });
+        }
+
+        Some(builder)
+    }
+
+    /// Panics if the builder already holds `MAX_SORTS` sorts.
+    pub fn add_sort(&mut self, name: &str, sort: Sort_) -> u8 {
+        assert!(self.sorts.len() < MAX_SORTS, "too many sorts");
+
+        self.statements.push(StatementEntry {
+            code: opcode::Statement::Sort,
+            proof: None,
+        });
+
+        self.sorts.push((name.to_string(), sort));
+        (self.sorts.len() - 1) as u8
+    }
+
+    pub fn add_term(&mut self, name: &str, binders: &[Var_], ret: Var_) -> u32 {
+        self.statements.push(StatementEntry {
+            code: opcode::Statement::TermDef,
+            proof: None,
+        });
+
+        self.terms.push(TermEntry {
+            name: name.to_string(),
+            sort: ret.get_sort_idx(),
+            binders: binders.to_vec(),
+            ret,
+            unify: Vec::new(),
+        });
+
+        (self.terms.len() - 1) as u32
+    }
+
+    pub fn add_def(
+        &mut self,
+        name: &str,
+        binders: &[Var_],
+        ret: Var_,
+        unify: &[Command<opcode::Unify>],
+        proof: &[Command<opcode::Proof>],
+        local: bool,
+    ) -> u32 {
+        let code = if local {
+            opcode::Statement::LocalDef
+        } else {
+            opcode::Statement::TermDef
+        };
+
+        self.statements.push(StatementEntry {
+            code,
+            proof: Some(proof.to_vec()),
+        });
+
+        self.terms.push(TermEntry {
+            name: name.to_string(),
+            sort: ret.get_sort_idx() | 0x80,
+            binders: binders.to_vec(),
+            ret,
+            unify: unify.to_vec(),
+        });
+
+        (self.terms.len() - 1) as u32
+    }
+
+    pub fn add_axiom(
+        &mut self,
+        name: &str,
+        binders: &[Var_],
+        unify: &[Command<opcode::Unify>],
+        proof: &[Command<opcode::Proof>],
+    ) -> u32 {
+        self.add_assertion(opcode::Statement::Axiom, name, binders, unify, proof)
+    }
+
+    /// Adds a theorem, where `local` theorems are not exported.
+    pub fn add_theorem(
+        &mut self,
+        name: &str,
+        binders: &[Var_],
+        unify: &[Command<opcode::Unify>],
+        proof: &[Command<opcode::Proof>],
+        local: bool,
+    ) -> u32 {
+        let code = if local {
+            opcode::Statement::LocalTerm
+        } else {
+            opcode::Statement::Thm
+        };
+
+        self.add_assertion(code, name, binders, unify, proof)
+    }
+
+    fn add_assertion(
+        &mut self,
+        code: opcode::Statement,
+        name: &str,
+        binders: &[Var_],
+        unify: &[Command<opcode::Unify>],
+        proof: &[Command<opcode::Proof>],
+    ) -> u32 {
+        self.statements.push(StatementEntry {
+            code,
+            proof: Some(proof.to_vec()),
+        });
+
+        self.theorems.push(TheoremEntry {
+            name: name.to_string(),
+            binders: binders.to_vec(),
+            unify: unify.to_vec(),
+        });
+
+        (self.theorems.len() - 1) as u32
+    }
+
+    /// Writes the file, including an index with the names of all sorts,
+    /// terms and theorems.
+    ///
+    /// Panics if there are more than `MAX_SORTS` sorts.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        assert!(self.sorts.len() <= MAX_SORTS, "too many sorts");
+
+        let mut out = vec![0; HEADER_SIZE];
+
+        out.extend(self.sorts.iter().map(|x| (x.1).0));
+        align(&mut out);
+
+        let terms_ptr = out.len();
+        out.resize(terms_ptr + 8 * self.terms.len(), 0);
+
+        let theorems_ptr = out.len();
+        out.resize(theorems_ptr + 8 * self.theorems.len(), 0);
+
+        for (i, term) in self.terms.iter().enumerate() {
+            let ptr = out.len();
+            let entry = terms_ptr + 8 * i;
+
+            out[entry..(entry + 2)].copy_from_slice(&(term.binders.len() as u16).to_le_bytes());
+            out[entry + 2] = term.sort;
+            out[(entry + 4)..(entry + 8)].copy_from_slice(&(ptr as u32).to_le_bytes());
+
+            for j in term.binders.iter().chain(Some(&term.ret)) {
+                out.extend(&encode_var(j).to_le_bytes());
+            }
+
+            if term.sort & 0x80 != 0 {
+                write_stream(&mut out, &term.unify, |x| x as u8);
+            }
+
+            align(&mut out);
+        }
+
+        for (i, thm) in self.theorems.iter().enumerate() {
+            let ptr = out.len();
+            let entry = theorems_ptr + 8 * i;
+
+            out[entry..(entry + 2)].copy_from_slice(&(thm.binders.len() as u16).to_le_bytes());
+            out[(entry + 4)..(entry + 8)].copy_from_slice(&(ptr as u32).to_le_bytes());
+
+            for j in &thm.binders {
+                out.extend(&encode_var(j).to_le_bytes());
+            }
+
+            write_stream(&mut out, &thm.unify, |x| x as u8);
+            align(&mut out);
+        }
+
+        let proofs_ptr = out.len();
+        let mut offsets = Vec::with_capacity(self.statements.len());
+
+        for i in &self.statements {
+            offsets.push(out.len());
+
+            let mut body = Vec::new();
+
+            if let Some(proof) = &i.proof {
+                write_stream(&mut body, proof, |x| x as u8);
+            }
+
+            write_statement(&mut out, i.code as u8, &body);
+        }
+
+        out.push(opcode::Statement::End as u8);
+        align(&mut out);
+
+        let index_ptr = out.len();
+        self.write_index(&mut out, &offsets);
+
+        out[0..4].copy_from_slice(b"MM0B");
+        out[4] = VERSION;
+        out[5] = self.sorts.len() as u8;
+        out[8..12].copy_from_slice(&(self.terms.len() as u32).to_le_bytes());
+        out[12..16].copy_from_slice(&(self.theorems.len() as u32).to_le_bytes());
+        out[16..20].copy_from_slice(&(terms_ptr as u32).to_le_bytes());
+        out[20..24].copy_from_slice(&(theorems_ptr as u32).to_le_bytes());
+        out[24..28].copy_from_slice(&(proofs_ptr as u32).to_le_bytes());
+        out[32..40].copy_from_slice(&(index_ptr as u64).to_le_bytes());
+
+        out
+    }
+
+    /// Writes the index, where the entries point to their statements and are
+    /// not linked into a search tree.
+    fn write_index(&self, out: &mut Vec<u8>, offsets: &[usize]) {
+        let mut sorts = Vec::new();
+        let mut terms = Vec::new();
+        let mut theorems = Vec::new();
+
+        for (i, statement) in self.statements.iter().enumerate() {
+            let list = match statement.code {
+                opcode::Statement::Sort => &mut sorts,
+                opcode::Statement::TermDef | opcode::Statement::LocalDef => &mut terms,
+                opcode::Statement::End => continue,
+                _ => &mut theorems,
+            };
+
+            list.push((offsets[i], statement.code as u8));
+        }
+
+        let names = self
+            .sorts
+            .iter()
+            .map(|x| &x.0)
+            .zip(sorts)
+            .chain(self.terms.iter().map(|x| &x.name).zip(terms))
+            .chain(self.theorems.iter().map(|x| &x.name).zip(theorems));
+
+        let table = out.len() + 8;
+        let len = self.sorts.len() + self.terms.len() + self.theorems.len();
+        out.resize(table + 8 * len, 0);
+
+        for (i, (name, (offset, kind))) in names.enumerate() {
+            let ptr = out.len();
+            out[(table + 8 * i)..(table + 8 * i + 8)].copy_from_slice(&(ptr as u64).to_le_bytes());
+
+            out.resize(ptr + 24, 0);
+            out.extend(&(offset as u64).to_le_bytes());
+            out.extend(&(i as u32).to_le_bytes());
+            out.push(kind);
+            out.extend(name.as_bytes());
+            out.push(0);
+            align(out);
+        }
+    }
+}
+
+fn strip_end(stream: &[Command<opcode::Unify>]) -> Vec<Command<opcode::Unify>> {
+    match stream.split_last() {
+        Some((last, rest)) if last.opcode == opcode::Unify::End => rest.to_vec(),
+        _ => stream.to_vec(),
+    }
+}
+
+fn encode_var(var: &Var_) -> u64 {
+    let sort = (var.get_sort_idx() as u64) << 56;
+    let bound = if var.is_bound() { 1 << 63 } else { 0 };
+
+    bound | sort | var.get_deps()
+}
+
+fn align(out: &mut Vec<u8>) {
+    while !out.len().is_multiple_of(8) {
+        out.push(0);
+    }
+}
+
+fn write_command(out: &mut Vec<u8>, opcode: u8, operand: u32) {
+    if operand == 0 {
+        out.push(opcode);
+    } else if operand <= u8::MAX as u32 {
+        out.push(opcode | 0x40);
+        out.push(operand as u8);
+    } else if operand <= u16::MAX as u32 {
+        out.push(opcode | 0x80);
+        out.extend(&(operand as u16).to_le_bytes());
+    } else {
+        out.push(opcode | 0xC0);
+        out.extend(&operand.to_le_bytes());
+    }
+}
+
+fn write_stream<T: Copy, F: Fn(T) -> u8>(out: &mut Vec<u8>, stream: &[Command<T>], f: F) {
+    for i in stream {
+        write_command(out, f(i.opcode), i.operand);
+    }
+
+    out.push(0);
+}
+
+/// Writes a statement, whose operand is the length of the statement
+/// including the command itself.
+fn write_statement(out: &mut Vec<u8>, opcode: u8, body: &[u8]) {
+    let len = body.len();
+
+    if len + 2 <= u8::MAX as usize {
+        out.push(opcode | 0x40);
+        out.push((len + 2) as u8);
+    } else if len + 3 <= u16::MAX as usize {
+        out.push(opcode | 0x80);
+        out.extend(&((len + 3) as u16).to_le_bytes());
+    } else {
+        out.push(opcode | 0xC0);
+        out.extend(&((len + 5) as u32).to_le_bytes());
+    }
+
+    out.extend(body);
+}
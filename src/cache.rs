@@ -0,0 +1,283 @@
+//! A cache of the statements that were verified before, so that a file that
+//! only grows does not have to be checked again from the start.
+//!
+//! Every statement is identified by a SHA-256 hash of its bytes, its proof,
+//! its unify stream, its binders and the hashes of every sort, term and
+//! theorem it refers to. A statement with the same hash is the same
+//! statement over the same dependencies, so it verifies if it did before.
+
+use crate::kernel::opcode::{self, Command};
+use crate::kernel::{KResult, Var, Var_};
+use crate::mmb_visitor::MmbVisitor;
+use crate::Verifier;
+use core::fmt::{self, Display, Formatter};
+use mmb_parser::Mmb;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+pub type Hash = [u8; 32];
+
+/// The first line of a cache file, a cache written by another version is
+/// discarded.
+const HEADER: &str = concat!("trivial-verifier-cache ", env!("CARGO_PKG_VERSION"));
+
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Cache {
+    verified: HashSet<Hash>,
+}
+
+impl Cache {
+    pub fn new() -> Cache {
+        Cache::default()
+    }
+
+    /// Reads a cache written by `Display`. Returns `None` if the cache is
+    /// malformed or written by another version.
+    pub fn parse(text: &str) -> Option<Cache> {
+        let mut lines = text.lines();
+
+        if lines.next()? != HEADER {
+            return None;
+        }
+
+        let verified = lines
+            .filter(|x| !x.is_empty())
+            .map(parse_hash)
+            .collect::<Option<_>>()?;
+
+        Some(Cache { verified })
+    }
+
+    pub fn contains(&self, hash: &Hash) -> bool {
+        self.verified.contains(hash)
+    }
+
+    pub fn insert(&mut self, hash: Hash) {
+        self.verified.insert(hash);
+    }
+
+    pub fn len(&self) -> usize {
+        self.verified.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.verified.is_empty()
+    }
+}
+
+impl Display for Cache {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        writeln!(f, "{}", HEADER)?;
+
+        let mut hashes: Vec<_> = self.verified.iter().collect();
+        hashes.sort();
+
+        for i in hashes {
+            writeln!(f, "{}", to_hex(i))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|x| format!("{:02x}", x)).collect()
+}
+
+fn parse_hash(text: &str) -> Option<Hash> {
+    let mut hash = [0; 32];
+
+    if text.len() != 64 || !text.is_ascii() {
+        return None;
+    }
+
+    for (i, x) in hash.iter_mut().enumerate() {
+        *x = u8::from_str_radix(&text[2 * i..2 * i + 2], 16).ok()?;
+    }
+
+    Some(hash)
+}
+
+/// The hashes of the sorts, terms and theorems that were seen so far.
+#[derive(Default)]
+struct Hashes {
+    sorts: Vec<Hash>,
+    terms: Vec<Hash>,
+    theorems: Vec<Hash>,
+}
+
+impl Hashes {
+    fn sort(&self, hasher: &mut Sha256, idx: u32) {
+        if let Some(x) = self.sorts.get(idx as usize) {
+            hasher.update(b"s");
+            hasher.update(x);
+        }
+    }
+
+    fn term(&self, hasher: &mut Sha256, idx: u32) {
+        if let Some(x) = self.terms.get(idx as usize) {
+            hasher.update(b"t");
+            hasher.update(x);
+        }
+    }
+
+    fn theorem(&self, hasher: &mut Sha256, idx: u32) {
+        if let Some(x) = self.theorems.get(idx as usize) {
+            hasher.update(b"a");
+            hasher.update(x);
+        }
+    }
+
+    fn binder(&self, hasher: &mut Sha256, var: &Var_) {
+        self.sort(hasher, var.get_sort_idx() as u32);
+        hasher.update(var.get_deps().to_le_bytes());
+        hasher.update([var.is_bound() as u8]);
+    }
+
+    fn unify(&self, hasher: &mut Sha256, stream: &[Command<opcode::Unify>]) {
+        use opcode::Unify;
+
+        for i in stream {
+            match i.opcode {
+                Unify::Term | Unify::TermSave => self.term(hasher, i.operand),
+                Unify::Dummy => self.sort(hasher, i.operand),
+                _ => {}
+            }
+        }
+    }
+
+    fn proof(&self, hasher: &mut Sha256, stream: &[Command<opcode::Proof>]) {
+        use opcode::Proof;
+
+        for i in stream {
+            hasher.update([i.opcode as u8]);
+            hasher.update(i.operand.to_le_bytes());
+
+            match i.opcode {
+                Proof::Term | Proof::TermSave => self.term(hasher, i.operand),
+                Proof::Thm | Proof::ThmSave => self.theorem(hasher, i.operand),
+                Proof::Dummy => self.sort(hasher, i.operand),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Computes the hash of every statement of a file, in the order of
+/// `Verifier::get_statements`.
+pub fn statement_hashes(data: &[u8]) -> Option<Vec<Hash>> {
+    let mmb = Mmb::from(data)?;
+
+    let mut visitor = MmbVisitor::new();
+    mmb.visit(&mut visitor).ok()?;
+
+    let slices = visitor.get_slices().to_vec();
+    let (table, stream) = visitor.into_table_owned();
+
+    // the statements come first, then the unify streams of the terms and the
+    // theorems
+    let (statement_slices, unify_slices) = slices.split_at(
+        slices
+            .len()
+            .checked_sub(table.terms.len() + table.theorems.len())?,
+    );
+
+    let mut seen = Hashes::default();
+    let mut hashes = Vec::new();
+
+    for (i, statement) in stream.get_statements().iter().enumerate() {
+        let mut hasher = Sha256::new();
+        hasher.update(statement_slices.get(i)?);
+
+        if let Some(proof) = stream.get_proof(i) {
+            seen.proof(&mut hasher, proof);
+        }
+
+        use opcode::Statement;
+        match statement.code {
+            Statement::End => continue,
+            Statement::Sort => {
+                let sort = table.sorts.get(seen.sorts.len())?;
+                hasher.update([sort.0]);
+
+                seen.sorts.push(hasher.finalize().into());
+                hashes.push(*seen.sorts.last()?);
+            }
+            Statement::TermDef | Statement::LocalDef => {
+                let idx = seen.terms.len();
+                let term = table.terms.get(idx)?;
+
+                hasher.update([term.sort]);
+                hasher.update(unify_slices.get(idx)?);
+
+                for i in table.binders.get(term.binders.clone())? {
+                    seen.binder(&mut hasher, i);
+                }
+
+                seen.binder(&mut hasher, &term.ret_type);
+                seen.sort(&mut hasher, (term.sort & 0x7F) as u32);
+                seen.unify(&mut hasher, table.unify.get(term.unify_commands.clone())?);
+
+                seen.terms.push(hasher.finalize().into());
+                hashes.push(*seen.terms.last()?);
+            }
+            _ => {
+                let idx = seen.theorems.len();
+                let thm = table.theorems.get(idx)?;
+
+                hasher.update(unify_slices.get(table.terms.len() + idx)?);
+
+                for i in table.binders.get(thm.binders.clone())? {
+                    seen.binder(&mut hasher, i);
+                }
+
+                seen.unify(&mut hasher, table.unify.get(thm.unify_commands.clone())?);
+
+                seen.theorems.push(hasher.finalize().into());
+                hashes.push(*seen.theorems.last()?);
+            }
+        }
+    }
+
+    Some(hashes)
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Mode {
+    /// Statements that are in the cache are not verified again.
+    TrustCache,
+    /// Every statement is verified, and the cache is only updated.
+    FullCheck,
+}
+
+/// Verifies the statements of a file from the start, and adds every
+/// statement that verifies to the cache.
+///
+/// `hashes` are the hashes of the statements, see `statement_hashes`.
+/// Returns the number of statements that were skipped.
+pub fn run(
+    verifier: &mut Verifier,
+    hashes: &[Hash],
+    cache: &mut Cache,
+    mode: Mode,
+) -> KResult<usize> {
+    let mut skipped = 0;
+    let mut next = None;
+
+    for (i, hash) in hashes.iter().enumerate() {
+        if mode == Mode::TrustCache && cache.contains(hash) {
+            skipped += 1;
+            continue;
+        }
+
+        if next != Some(i) {
+            verifier.seek(i);
+        }
+
+        verifier.run_statement(&mut |_, _| {})?;
+        cache.insert(*hash);
+        next = Some(i + 1);
+    }
+
+    Ok(skipped)
+}
@@ -0,0 +1,160 @@
+//! A certificate that records which file was verified and by what.
+//!
+//! ```text
+//! {
+//!   "format": "trivial-verifier-certificate",
+//!   "version": 3,
+//!   "input": sha-256 of the file,
+//!   "verifier": version of this crate,
+//!   "kernel": version of `trivial-kernel` it was built with,
+//!   "sorts", "terms", "theorems": counts,
+//!   "axioms": names of the axioms that some proof refers to,
+//!   "statements": sha-256 of the exported statements, see `mm0::export`
+//! }
+//! ```
+//!
+//! Everything except the versions of the verifier and the kernel is
+//! determined by the file, so a certificate can be checked against a file
+//! without verifying the proofs.
+
+use crate::cache::to_hex;
+use crate::kernel::{opcode, KResult};
+use crate::Verifier;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+pub const FORMAT: &str = "trivial-verifier-certificate";
+
+pub const VERSION: u32 = 3;
+
+/// The version of `trivial-kernel` in the lock file, as found by the build
+/// script.
+pub const KERNEL_VERSION: &str = env!("TRIVIAL_KERNEL_VERSION");
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Certificate {
+    pub input: String,
+    pub verifier: String,
+    pub kernel: String,
+    pub sorts: usize,
+    pub terms: usize,
+    pub theorems: usize,
+    pub axioms: Vec<String>,
+    pub statements: String,
+}
+
+fn sha256(data: &[u8]) -> String {
+    to_hex(&Sha256::digest(data))
+}
+
+impl Certificate {
+    /// Creates the certificate of a file, which should only be handed out
+    /// once the verifier ran to completion.
+    pub fn new(data: &[u8], verifier: &Verifier) -> KResult<Certificate> {
+        let statements = verifier.get_statements();
+        let mut used = vec![false; verifier.table.theorems.len()];
+
+        for (i, _) in statements.iter().enumerate() {
+            for c in verifier.get_proof(i).unwrap_or(&[]) {
+                if let opcode::Proof::Thm | opcode::Proof::ThmSave = c.opcode {
+                    if let Some(x) = used.get_mut(c.operand as usize) {
+                        *x = true;
+                    }
+                }
+            }
+        }
+
+        let axioms = statements
+            .iter()
+            .filter(|x| x.kind == opcode::Statement::Axiom && used[x.index])
+            .map(|x| match verifier.get_theorem_name(x.index) {
+                Some(name) => name.to_string(),
+                None => format!("thm{}", x.index),
+            })
+            .collect();
+
+        Ok(Certificate {
+            input: sha256(data),
+            verifier: env!("CARGO_PKG_VERSION").to_string(),
+            kernel: KERNEL_VERSION.to_string(),
+            sorts: verifier.table.sorts.len(),
+            terms: verifier.table.terms.len(),
+            theorems: verifier.table.theorems.len(),
+            axioms,
+            statements: sha256(crate::mm0::export(verifier)?.as_bytes()),
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        json!({
+            "format": FORMAT,
+            "version": VERSION,
+            "input": self.input,
+            "verifier": self.verifier,
+            "kernel": self.kernel,
+            "sorts": self.sorts,
+            "terms": self.terms,
+            "theorems": self.theorems,
+            "axioms": self.axioms,
+            "statements": self.statements,
+        })
+    }
+
+    /// Reads a certificate written by `to_json`.
+    pub fn from_json(value: &Value) -> Option<Certificate> {
+        if value["format"] != FORMAT || value["version"] != VERSION {
+            return None;
+        }
+
+        let string = |key: &str| value[key].as_str().map(|x| x.to_string());
+        let count = |key: &str| value[key].as_u64().map(|x| x as usize);
+
+        Some(Certificate {
+            input: string("input")?,
+            verifier: string("verifier")?,
+            kernel: string("kernel")?,
+            sorts: count("sorts")?,
+            terms: count("terms")?,
+            theorems: count("theorems")?,
+            axioms: value["axioms"]
+                .as_array()?
+                .iter()
+                .map(|x| x.as_str().map(|x| x.to_string()))
+                .collect::<Option<_>>()?,
+            statements: string("statements")?,
+        })
+    }
+
+    /// Returns the fields that differ from the certificate of the same file.
+    /// A certificate made by another version of the verifier or the kernel
+    /// never matches.
+    pub fn mismatches(&self, other: &Certificate) -> Vec<&'static str> {
+        let mut fields = Vec::new();
+
+        if self.input != other.input {
+            fields.push("input");
+        }
+
+        if self.verifier != other.verifier {
+            fields.push("verifier");
+        }
+
+        if self.kernel != other.kernel {
+            fields.push("kernel");
+        }
+
+        if (self.sorts, self.terms, self.theorems) != (other.sorts, other.terms, other.theorems) {
+            fields.push("counts");
+        }
+
+        if self.axioms != other.axioms {
+            fields.push("axioms");
+        }
+
+        if self.statements != other.statements {
+            fields.push("statements");
+        }
+
+        fields
+    }
+}
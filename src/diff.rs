@@ -0,0 +1,504 @@
+use crate::expr::Expr;
+use crate::kernel::opcode::{self, Command};
+use crate::kernel::{error::Kind, KResult, Table, Term, Theorem, Var, Var_};
+use crate::verifier::StatementInfo;
+use crate::Verifier;
+use core::fmt::{self, Display, Formatter};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Change {
+    Added,
+    Removed,
+    /// The theorem was removed, and one with the same statement was added
+    /// under the given name.
+    Renamed(String),
+    /// The sort modifiers changed.
+    Modifiers,
+    /// The binders or the return type of a term changed, or a term became a
+    /// definition or the other way around.
+    Signature,
+    /// The value of a definition changed.
+    Value,
+    /// The binders, hypotheses or conclusion of a theorem changed.
+    Statement,
+    /// Only the proof of a theorem changed.
+    Proof,
+    /// A theorem became an axiom, or the other way around if `axiom` is
+    /// false.
+    Kind {
+        axiom: bool,
+    },
+    /// A statement became local or public.
+    Visibility,
+}
+
+/// A change of the sort, term or theorem `name`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Difference {
+    pub name: String,
+    pub change: Change,
+    /// Whether the statement is exported, by the new file for additions and
+    /// by the old file otherwise.
+    pub public: bool,
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.change {
+            Change::Added => write!(f, "+ {}", self.name),
+            Change::Removed => write!(f, "- {}", self.name),
+            Change::Renamed(to) => write!(f, "> {} -> {}", self.name, to),
+            Change::Modifiers => write!(f, "~ {}: sort modifiers changed", self.name),
+            Change::Signature => write!(f, "~ {}: signature changed", self.name),
+            Change::Value => write!(f, "~ {}: value changed", self.name),
+            Change::Statement => write!(f, "~ {}: statement changed", self.name),
+            Change::Proof => write!(f, "~ {}: proof changed", self.name),
+            Change::Kind { axiom: true } => write!(f, "~ {}: became an axiom", self.name),
+            Change::Kind { axiom: false } => write!(f, "~ {}: became a theorem", self.name),
+            Change::Visibility => write!(f, "~ {}: visibility changed", self.name),
+        }
+    }
+}
+
+/// The differences between two files, grouped by kind.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Diff {
+    pub sorts: Vec<Difference>,
+    pub terms: Vec<Difference>,
+    pub theorems: Vec<Difference>,
+}
+
+impl Difference {
+    /// Whether the change can break a file that imports the public
+    /// statements of the old file.
+    ///
+    /// Additions, proof changes, axioms that are proved, and statements that
+    /// become public are compatible. A changed theorem statement is always
+    /// breaking, since it is not checked whether the new statement is at
+    /// least as strong.
+    pub fn is_breaking(&self) -> bool {
+        match self.change {
+            Change::Added | Change::Proof | Change::Kind { axiom: false } => false,
+            Change::Kind { axiom: true } => self.public,
+            Change::Removed
+            | Change::Renamed(_)
+            | Change::Modifiers
+            | Change::Signature
+            | Change::Value
+            | Change::Statement
+            | Change::Visibility => self.public,
+        }
+    }
+}
+
+impl Diff {
+    pub fn is_empty(&self) -> bool {
+        self.sorts.is_empty() && self.terms.is_empty() && self.theorems.is_empty()
+    }
+
+    /// The changes that break compatibility, see `Difference::is_breaking`.
+    pub fn breaking(&self) -> impl Iterator<Item = &Difference> {
+        self.sorts
+            .iter()
+            .chain(&self.terms)
+            .chain(&self.theorems)
+            .filter(|x| x.is_breaking())
+    }
+}
+
+/// The statement of a theorem, with sorts and terms numbered as in the new
+/// file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+struct Signature {
+    binders: Vec<Var_>,
+    hyps: Vec<Expr>,
+    conclusion: Expr,
+}
+
+/// Matches the sorts, terms and theorems of the old file with those of the
+/// new file by name.
+struct Matching<'a> {
+    old: &'a Verifier,
+    sorts: Vec<Option<u32>>,
+    terms: Vec<Option<u32>>,
+    theorems: Vec<Option<u32>>,
+}
+
+fn match_names<'a, F, G>(len: usize, name: F, index: G) -> Vec<Option<u32>>
+where
+    F: Fn(usize) -> Option<&'a str>,
+    G: Fn(&str) -> Option<usize>,
+{
+    (0..len)
+        .map(|i| name(i).and_then(&index).map(|x| x as u32))
+        .collect()
+}
+
+impl<'a> Matching<'a> {
+    fn new(old: &'a Verifier, new: &'a Verifier) -> Matching<'a> {
+        let counts = counts(old);
+
+        Matching {
+            old,
+            sorts: match_names(
+                counts.0,
+                |x| old.get_sort_name(x),
+                |x| new.get_sort_index(x),
+            ),
+            terms: match_names(
+                counts.1,
+                |x| old.get_term_name(x),
+                |x| new.get_term_index(x),
+            ),
+            theorems: match_names(
+                counts.2,
+                |x| old.get_theorem_name(x),
+                |x| new.get_theorem_index(x),
+            ),
+        }
+    }
+
+    fn var(&self, var: &Var_) -> Option<Var_> {
+        let sort = self.sorts.get(var.get_sort_idx() as usize).copied()??;
+
+        Some(Var_::new(sort as u8, var.get_deps(), var.is_bound()))
+    }
+
+    fn binders(&self, binders: &[Var_]) -> Option<Vec<Var_>> {
+        binders.iter().map(|x| self.var(x)).collect()
+    }
+
+    fn expr(&self, expr: &Expr) -> Option<Expr> {
+        match expr {
+            Expr::Var(x) => Some(Expr::Var(*x)),
+            Expr::Term(id, args) => {
+                let id = self.terms.get(*id as usize).copied()??;
+                let args = args.iter().map(|x| self.expr(x)).collect::<Option<_>>()?;
+
+                Some(Expr::Term(id, args))
+            }
+        }
+    }
+
+    fn proof(&self, proof: &[Command<opcode::Proof>]) -> Option<Vec<Command<opcode::Proof>>> {
+        use opcode::Proof;
+
+        proof
+            .iter()
+            .map(|x| {
+                let operand = match x.opcode {
+                    Proof::Term | Proof::TermSave => {
+                        self.terms.get(x.operand as usize).copied()??
+                    }
+                    Proof::Thm | Proof::ThmSave => {
+                        self.theorems.get(x.operand as usize).copied()??
+                    }
+                    Proof::Dummy => self.sorts.get(x.operand as usize).copied()??,
+                    _ => x.operand,
+                };
+
+                Some(Command {
+                    opcode: x.opcode,
+                    operand,
+                })
+            })
+            .collect()
+    }
+
+    /// Translates the statement of an old theorem, which is `None` if it
+    /// mentions something the new file does not have.
+    fn theorem(&self, id: u32) -> KResult<Option<Signature>> {
+        let sig = signature(self.old, id)?;
+
+        let translated = (|| {
+            Some(Signature {
+                binders: self.binders(&sig.binders)?,
+                hyps: sig
+                    .hyps
+                    .iter()
+                    .map(|x| self.expr(x))
+                    .collect::<Option<_>>()?,
+                conclusion: self.expr(&sig.conclusion)?,
+            })
+        })();
+
+        Ok(translated)
+    }
+}
+
+fn counts(verifier: &Verifier) -> (usize, usize, usize) {
+    let table = &verifier.table;
+    let mut counts = (0, 0, 0);
+
+    while table.get_sort(counts.0 as u8).is_some() {
+        counts.0 += 1;
+    }
+
+    while table.get_term(counts.1 as u32).is_some() {
+        counts.1 += 1;
+    }
+
+    while table.get_theorem(counts.2 as u32).is_some() {
+        counts.2 += 1;
+    }
+
+    counts
+}
+
+fn signature(verifier: &Verifier, id: u32) -> KResult<Signature> {
+    let thm = verifier.table.get_theorem(id).ok_or(Kind::InvalidTheorem)?;
+    let binders = verifier
+        .table
+        .get_binders(thm.get_binders())
+        .ok_or(Kind::InvalidBinderIndices)?;
+
+    let (hyps, conclusion) = verifier.get_theorem_statement(id)?;
+
+    Ok(Signature {
+        binders: binders.to_vec(),
+        hyps,
+        conclusion,
+    })
+}
+
+/// The statements of a file by kind, as indices into `get_statements`.
+struct Statements {
+    all: Vec<StatementInfo>,
+    terms: Vec<usize>,
+    theorems: Vec<usize>,
+}
+
+impl Statements {
+    fn new(verifier: &Verifier) -> Statements {
+        let all = verifier.get_statements();
+        let mut terms = Vec::new();
+        let mut theorems = Vec::new();
+
+        for (i, x) in all.iter().enumerate() {
+            if x.is_term() {
+                terms.push(i);
+            } else if x.is_theorem() {
+                theorems.push(i);
+            }
+        }
+
+        Statements {
+            all,
+            terms,
+            theorems,
+        }
+    }
+
+    fn term(&self, idx: usize) -> Option<&StatementInfo> {
+        self.all.get(*self.terms.get(idx)?)
+    }
+
+    fn theorem(&self, idx: usize) -> Option<(usize, &StatementInfo)> {
+        let statement = *self.theorems.get(idx)?;
+
+        Some((statement, self.all.get(statement)?))
+    }
+}
+
+fn is_public(info: Option<&StatementInfo>) -> bool {
+    info.map(|x| !x.is_local()).unwrap_or(false)
+}
+
+/// Compares two files, matching sorts, terms and theorems by name.
+///
+/// Statements are compared structurally, so renumbering the terms of a file
+/// is not a change. Theorems that are removed and added with the same
+/// statement are reported as renamed.
+pub fn diff(old: &Verifier, new: &Verifier) -> KResult<Diff> {
+    let matching = Matching::new(old, new);
+    let reverse = Matching::new(new, old);
+    let old_statements = Statements::new(old);
+    let new_statements = Statements::new(new);
+    let mut diff = Diff::default();
+
+    let difference = |name: Option<&str>, change, public| Difference {
+        name: name.unwrap_or("<unnamed>").to_string(),
+        change,
+        public,
+    };
+
+    // sorts are always public
+    for (i, x) in matching.sorts.iter().enumerate() {
+        let name = old.get_sort_name(i);
+
+        let change = match x {
+            None => Change::Removed,
+            Some(x) if old.table.get_sort(i as u8) != new.table.get_sort(*x as u8) => {
+                Change::Modifiers
+            }
+            _ => continue,
+        };
+
+        diff.sorts.push(difference(name, change, true));
+    }
+
+    for (i, _) in reverse.sorts.iter().enumerate().filter(|x| x.1.is_none()) {
+        diff.sorts
+            .push(difference(new.get_sort_name(i), Change::Added, true));
+    }
+
+    for (i, x) in matching.terms.iter().enumerate() {
+        let name = old.get_term_name(i);
+        let info = old_statements.term(i);
+        let public = is_public(info);
+
+        let x = match x {
+            Some(x) => *x,
+            None => {
+                diff.terms.push(difference(name, Change::Removed, public));
+                continue;
+            }
+        };
+
+        let term = old.table.get_term(i as u32);
+        let other = new.table.get_term(x);
+
+        let same_signature = match (term, other) {
+            (Some(a), Some(b)) => {
+                let binders = old.table.get_binders(a.get_binders());
+                let other = new.table.get_binders(b.get_binders());
+
+                a.is_definition() == b.is_definition()
+                    && binders.and_then(|x| matching.binders(x)).as_deref() == other
+                    && matching.var(a.get_return_type()).as_ref() == Some(b.get_return_type())
+            }
+            _ => false,
+        };
+
+        if !same_signature {
+            diff.terms.push(difference(name, Change::Signature, public));
+        } else {
+            let value = old.get_definition_value(i as u32)?;
+            let value = value.map(|(e, d)| (matching.expr(&e), d));
+            let other = new.get_definition_value(x)?.map(|(e, d)| (Some(e), d));
+
+            let dummies = |d: &[u8]| -> Option<Vec<u32>> {
+                d.iter()
+                    .map(|x| matching.sorts.get(*x as usize).copied().flatten())
+                    .collect()
+            };
+
+            let same = match (&value, &other) {
+                (Some((a, da)), Some((b, db))) => {
+                    a == b && dummies(da) == Some(db.iter().map(|x| *x as u32).collect())
+                }
+                (None, None) => true,
+                _ => false,
+            };
+
+            if !same {
+                diff.terms.push(difference(name, Change::Value, public));
+            }
+        }
+
+        if public != is_public(new_statements.term(x as usize)) {
+            diff.terms
+                .push(difference(name, Change::Visibility, public));
+        }
+    }
+
+    for (i, _) in reverse.terms.iter().enumerate().filter(|x| x.1.is_none()) {
+        let public = is_public(new_statements.term(i));
+        diff.terms
+            .push(difference(new.get_term_name(i), Change::Added, public));
+    }
+
+    // theorems of the new file that do not exist in the old one, by statement
+    let mut added: HashMap<Signature, Vec<usize>> = HashMap::new();
+
+    for (i, _) in reverse
+        .theorems
+        .iter()
+        .enumerate()
+        .filter(|x| x.1.is_none())
+    {
+        added.entry(signature(new, i as u32)?).or_default().push(i);
+    }
+
+    let mut renamed = Vec::new();
+
+    for (i, x) in matching.theorems.iter().enumerate() {
+        let name = old.get_theorem_name(i);
+        let (statement, info) = match old_statements.theorem(i) {
+            Some(x) => x,
+            None => continue,
+        };
+        let public = !info.is_local();
+
+        let x = match x {
+            Some(x) => *x,
+            None => {
+                let target = matching
+                    .theorem(i as u32)?
+                    .and_then(|sig| added.get_mut(&sig))
+                    .and_then(|x| x.pop());
+
+                let change = match target.and_then(|x| new.get_theorem_name(x)) {
+                    Some(to) => {
+                        renamed.push(target);
+                        Change::Renamed(to.to_string())
+                    }
+                    None => Change::Removed,
+                };
+
+                diff.theorems.push(difference(name, change, public));
+                continue;
+            }
+        };
+
+        let (other_statement, other_info) = match new_statements.theorem(x as usize) {
+            Some(x) => x,
+            None => continue,
+        };
+
+        if matching.theorem(i as u32)? != Some(signature(new, x)?) {
+            diff.theorems
+                .push(difference(name, Change::Statement, public));
+        } else {
+            let proof = old.get_proof(statement).and_then(|x| matching.proof(x));
+
+            if proof.as_deref() != new.get_proof(other_statement) {
+                diff.theorems.push(difference(name, Change::Proof, public));
+            }
+        }
+
+        let axiom = other_info.kind == opcode::Statement::Axiom;
+
+        if (info.kind == opcode::Statement::Axiom) != axiom {
+            diff.theorems
+                .push(difference(name, Change::Kind { axiom }, public));
+        }
+
+        if info.is_local() != other_info.is_local() {
+            diff.theorems
+                .push(difference(name, Change::Visibility, public));
+        }
+    }
+
+    for (i, _) in reverse
+        .theorems
+        .iter()
+        .enumerate()
+        .filter(|x| x.1.is_none())
+    {
+        if renamed.contains(&Some(i)) {
+            continue;
+        }
+
+        let public = new_statements
+            .theorem(i)
+            .map(|x| !x.1.is_local())
+            .unwrap_or(false);
+
+        diff.theorems
+            .push(difference(new.get_theorem_name(i), Change::Added, public));
+    }
+
+    Ok(diff)
+}
@@ -0,0 +1,491 @@
+//! Static HTML documentation of a file.
+//!
+//! `generate` returns the pages of the site, which `write` puts into a
+//! directory:
+//!
+//! ```text
+//! index.html      every sort, term and theorem of the file
+//! sort-<n>.html   a sort and the terms of that sort
+//! term-<n>.html   a term, its definition and the theorems mentioning it
+//! thm-<n>.html    a theorem, the axioms and theorems it uses, and the
+//!                 theorems using it
+//! style.css
+//! ```
+//!
+//! Expressions are printed in the notation of the specification if one is
+//! given, and as plain applications otherwise.
+
+use crate::expr::Expr;
+use crate::kernel::{error::Kind, opcode, Table, Term, Theorem, Var, Var_};
+use crate::mm0::{MathError, Mm0, Printer, Statement};
+use crate::Verifier;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 60em; margin: auto; padding: 1em; }
+code { font-family: monospace; }
+ul.names { columns: 4; }
+nav { margin-bottom: 1em; }
+";
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DocsError {
+    Kernel(Kind),
+    Math(MathError),
+}
+
+impl From<Kind> for DocsError {
+    fn from(kind: Kind) -> DocsError {
+        DocsError::Kernel(kind)
+    }
+}
+
+impl From<MathError> for DocsError {
+    fn from(error: MathError) -> DocsError {
+        DocsError::Math(error)
+    }
+}
+
+/// A file of the site, with its path relative to the root of the site.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Page {
+    pub path: String,
+    pub content: String,
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+
+    out
+}
+
+fn page(path: String, title: &str, body: &str) -> Page {
+    let content = format!(
+        "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<link rel=\"stylesheet\" href=\"style.css\">
+</head>
+<body>
+<nav><a href=\"index.html\">index</a></nav>
+{}</body>
+</html>
+",
+        escape(title),
+        body
+    );
+
+    Page { path, content }
+}
+
+/// Writes a list of links, or `none`.
+fn list(out: &mut String, title: &str, links: &[String]) {
+    let _ = writeln!(out, "<h2>{}</h2>", title);
+
+    if links.is_empty() {
+        out.push_str("<p>none</p>\n");
+        return;
+    }
+
+    out.push_str("<ul class=\"names\">\n");
+
+    for i in links {
+        let _ = writeln!(out, "<li>{}</li>", i);
+    }
+
+    out.push_str("</ul>\n");
+}
+
+fn collect_terms(expr: &Expr, terms: &mut Vec<usize>) {
+    if let Expr::Term(id, args) = expr {
+        terms.push(*id as usize);
+
+        for i in args {
+            collect_terms(i, terms);
+        }
+    }
+}
+
+struct Site<'a> {
+    verifier: &'a Verifier,
+    spec: &'a Mm0,
+    printer: Printer<'a>,
+    /// The theorems each theorem refers to in its proof.
+    uses: Vec<Vec<usize>>,
+    /// The theorems referring to each theorem in their proof.
+    used_by: Vec<Vec<usize>>,
+    /// The axioms each theorem depends on, transitively.
+    axioms: Vec<Vec<usize>>,
+    /// The terms occurring in the statement of each theorem.
+    mentions: Vec<Vec<usize>>,
+}
+
+impl<'a> Site<'a> {
+    fn new(verifier: &'a Verifier, spec: &'a Mm0) -> Result<Site<'a>, DocsError> {
+        let printer = Printer::new(verifier, spec)?;
+        let nr_theorems = verifier.table.theorems.len();
+
+        let mut uses = vec![Vec::new(); nr_theorems];
+        let mut used_by = vec![Vec::new(); nr_theorems];
+        let mut axioms = vec![Vec::new(); nr_theorems];
+        let mut mentions = vec![Vec::new(); nr_theorems];
+
+        for idx in 0..nr_theorems {
+            let (_, deps) = verifier.get_dependencies(idx);
+
+            for dep in deps.iter().filter(|x| **x < nr_theorems) {
+                used_by[*dep].push(idx);
+            }
+
+            let (hyps, conclusion) = verifier.get_theorem_statement(idx as u32)?;
+            let mut terms = Vec::new();
+
+            for i in hyps.iter().chain(Some(&conclusion)) {
+                collect_terms(i, &mut terms);
+            }
+
+            terms.sort_unstable();
+            terms.dedup();
+
+            uses[idx] = deps;
+            axioms[idx] = verifier.get_axioms(idx);
+            mentions[idx] = terms;
+        }
+
+        Ok(Site {
+            verifier,
+            spec,
+            printer,
+            uses,
+            used_by,
+            axioms,
+            mentions,
+        })
+    }
+
+    fn sort_name(&self, idx: usize) -> String {
+        match self.verifier.get_sort_name(idx) {
+            Some(name) => name.to_string(),
+            None => format!("s{}", idx),
+        }
+    }
+
+    fn term_name(&self, idx: usize) -> String {
+        match self.verifier.get_term_name(idx) {
+            Some(name) => name.to_string(),
+            None => format!("t{}", idx),
+        }
+    }
+
+    fn theorem_name(&self, idx: usize) -> String {
+        match self.verifier.get_theorem_name(idx) {
+            Some(name) => name.to_string(),
+            None => format!("thm{}", idx),
+        }
+    }
+
+    fn sort_link(&self, idx: usize) -> String {
+        format!(
+            "<a href=\"sort-{}.html\">{}</a>",
+            idx,
+            escape(&self.sort_name(idx))
+        )
+    }
+
+    fn term_link(&self, idx: usize) -> String {
+        format!(
+            "<a href=\"term-{}.html\">{}</a>",
+            idx,
+            escape(&self.term_name(idx))
+        )
+    }
+
+    fn theorem_link(&self, idx: usize) -> String {
+        format!(
+            "<a href=\"thm-{}.html\">{}</a>",
+            idx,
+            escape(&self.theorem_name(idx))
+        )
+    }
+
+    fn theorem_links(&self, theorems: &[usize]) -> Vec<String> {
+        theorems.iter().map(|x| self.theorem_link(*x)).collect()
+    }
+
+    /// Returns the sort of a variable, followed by the bound variables it
+    /// depends on.
+    fn type_of(&self, binders: &[Var_], names: &[String], ty: &Var_) -> String {
+        let mut out = self.sort_link(ty.get_sort_idx() as usize);
+
+        let bound = binders.iter().enumerate().filter(|(_, x)| x.is_bound());
+
+        for (bit, (idx, _)) in bound.enumerate() {
+            if ty.get_deps() & (1 << bit) != 0 {
+                let _ = write!(out, " {}", escape(&names[idx]));
+            }
+        }
+
+        out
+    }
+
+    /// Writes the binders, each preceded by a space.
+    fn binders(&self, binders: &[Var_], names: &[String]) -> String {
+        let binders: Vec<_> = binders
+            .iter()
+            .enumerate()
+            .map(|(i, ty)| {
+                if ty.is_bound() {
+                    format!(
+                        " {{{}: {}}}",
+                        escape(&names[i]),
+                        self.type_of(binders, names, ty)
+                    )
+                } else {
+                    format!(
+                        " ({}: {})",
+                        escape(&names[i]),
+                        self.type_of(binders, names, ty)
+                    )
+                }
+            })
+            .collect();
+
+        binders.concat()
+    }
+
+    /// Names the binders after the specification, or `v0`, `v1` and so on.
+    fn names(&self, name: &str, nr: usize) -> Vec<String> {
+        let binders = match self.spec.get_statement(name) {
+            Some(Statement::Term(term)) => Some(&term.binders),
+            Some(Statement::Assert(assert)) => Some(&assert.binders),
+            _ => None,
+        };
+
+        (0..nr)
+            .map(|i| {
+                binders
+                    .and_then(|x| x.get(i))
+                    .and_then(|x| x.name.clone())
+                    .unwrap_or_else(|| format!("v{}", i))
+            })
+            .collect()
+    }
+
+    fn index(&self) -> Page {
+        let table = &self.verifier.table;
+        let mut body = String::from("<h1>Index</h1>\n");
+
+        let sorts: Vec<_> = (0..table.sorts.len()).map(|x| self.sort_link(x)).collect();
+        let terms: Vec<_> = (0..table.terms.len()).map(|x| self.term_link(x)).collect();
+        let theorems: Vec<_> = (0..table.theorems.len()).collect();
+
+        list(&mut body, "Sorts", &sorts);
+        list(&mut body, "Terms", &terms);
+        list(&mut body, "Theorems", &self.theorem_links(&theorems));
+
+        page("index.html".to_string(), "Index", &body)
+    }
+
+    fn sort(&self, idx: usize) -> Option<Page> {
+        let table = &self.verifier.table;
+        let sort = table.get_sort(idx as u8)?;
+        let name = self.sort_name(idx);
+
+        let modifiers: Vec<_> = ["pure", "strict", "provable", "free"]
+            .iter()
+            .enumerate()
+            .filter(|(bit, _)| sort.0 & (1 << bit) != 0)
+            .map(|(_, x)| *x)
+            .collect();
+
+        let mut body = format!("<h1>sort {}</h1>\n", escape(&name));
+
+        if !modifiers.is_empty() {
+            let _ = writeln!(body, "<p>{}</p>", modifiers.join(" "));
+        }
+
+        let terms: Vec<_> = (0..table.terms.len())
+            .filter(|x| {
+                table
+                    .get_term(*x as u32)
+                    .is_some_and(|x| x.get_return_type().get_sort_idx() as usize == idx)
+            })
+            .map(|x| self.term_link(x))
+            .collect();
+
+        list(&mut body, "Terms", &terms);
+
+        Some(page(format!("sort-{}.html", idx), &name, &body))
+    }
+
+    fn term(&self, idx: usize, mentioned_by: &[usize]) -> Result<Page, DocsError> {
+        let table = &self.verifier.table;
+        let term = table.get_term(idx as u32).ok_or(Kind::InvalidTerm)?;
+        let binders = table
+            .get_binders(term.get_binders())
+            .ok_or(Kind::InvalidBinderIndices)?;
+
+        let name = self.term_name(idx);
+        let names = self.names(&name, binders.len());
+        let local = self
+            .verifier
+            .get_term_info(idx)
+            .is_some_and(|x| x.is_local());
+
+        let kind = match (local, term.is_definition()) {
+            (true, _) => "local def",
+            (false, true) => "def",
+            (false, false) => "term",
+        };
+
+        let mut body = format!("<h1>{} {}</h1>\n", kind, escape(&name));
+
+        let _ = writeln!(
+            body,
+            "<p><code>{}{}: {}</code></p>",
+            escape(&name),
+            self.binders(binders, &names),
+            self.type_of(binders, &names, term.get_return_type())
+        );
+
+        if let Some((value, _)) = self.verifier.get_definition_value(idx as u32)? {
+            let value = self.printer.to_display(&value, &names).to_string();
+            let _ = writeln!(
+                body,
+                "<h2>Value</h2>\n<p><code>{}</code></p>",
+                escape(&value)
+            );
+        }
+
+        list(&mut body, "Mentioned by", &self.theorem_links(mentioned_by));
+
+        Ok(page(format!("term-{}.html", idx), &name, &body))
+    }
+
+    fn theorem(&self, idx: usize) -> Result<Page, DocsError> {
+        let table = &self.verifier.table;
+        let thm = table.get_theorem(idx as u32).ok_or(Kind::InvalidTheorem)?;
+        let binders = table
+            .get_binders(thm.get_binders())
+            .ok_or(Kind::InvalidBinderIndices)?;
+
+        let name = self.theorem_name(idx);
+        let names = self.names(&name, binders.len());
+        let (hyps, conclusion) = self.printer.display_theorem(self.verifier, idx as u32)?;
+
+        let statement = self.verifier.get_theorem_info(idx).map(|x| x.kind);
+        let axiom = statement == Some(opcode::Statement::Axiom);
+
+        let kind = match statement {
+            Some(opcode::Statement::Axiom) => "axiom",
+            Some(opcode::Statement::LocalTerm) => "local theorem",
+            _ => "theorem",
+        };
+
+        let mut body = format!("<h1>{} {}</h1>\n", kind, escape(&name));
+
+        let _ = writeln!(
+            body,
+            "<p><code>{}{}</code></p>",
+            escape(&name),
+            self.binders(binders, &names)
+        );
+
+        if !hyps.is_empty() {
+            body.push_str("<h2>Hypotheses</h2>\n<ul>\n");
+
+            for i in &hyps {
+                let _ = writeln!(body, "<li><code>{}</code></li>", escape(i));
+            }
+
+            body.push_str("</ul>\n");
+        }
+
+        let _ = writeln!(
+            body,
+            "<h2>Conclusion</h2>\n<p><code>{}</code></p>",
+            escape(&conclusion)
+        );
+
+        let terms: Vec<_> = self.mentions[idx]
+            .iter()
+            .map(|x| self.term_link(*x))
+            .collect();
+        list(&mut body, "Terms", &terms);
+
+        if !axiom {
+            list(&mut body, "Axioms", &self.theorem_links(&self.axioms[idx]));
+            list(&mut body, "Uses", &self.theorem_links(&self.uses[idx]));
+        }
+
+        list(
+            &mut body,
+            "Used by",
+            &self.theorem_links(&self.used_by[idx]),
+        );
+
+        Ok(page(format!("thm-{}.html", idx), &name, &body))
+    }
+}
+
+/// Returns the pages documenting a file, with the notations and variable
+/// names of `mm0` if it is given.
+pub fn generate(verifier: &Verifier, mm0: Option<&Mm0>) -> Result<Vec<Page>, DocsError> {
+    let empty = Mm0::default();
+    let site = Site::new(verifier, mm0.unwrap_or(&empty))?;
+    let table = &verifier.table;
+
+    let mut mentioned_by = vec![Vec::new(); table.terms.len()];
+
+    for (thm, terms) in site.mentions.iter().enumerate() {
+        for i in terms {
+            if let Some(x) = mentioned_by.get_mut(*i) {
+                x.push(thm);
+            }
+        }
+    }
+
+    let mut pages = vec![
+        site.index(),
+        Page {
+            path: "style.css".to_string(),
+            content: STYLE.to_string(),
+        },
+    ];
+
+    pages.extend((0..table.sorts.len()).filter_map(|x| site.sort(x)));
+
+    for (i, theorems) in mentioned_by.iter().enumerate() {
+        pages.push(site.term(i, theorems)?);
+    }
+
+    for i in 0..table.theorems.len() {
+        pages.push(site.theorem(i)?);
+    }
+
+    Ok(pages)
+}
+
+/// Writes the pages into `dir`, which is created if it does not exist.
+pub fn write(dir: &Path, pages: &[Page]) -> io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+
+    for i in pages {
+        std::fs::write(dir.join(&i.path), &i.content)?;
+    }
+
+    Ok(())
+}
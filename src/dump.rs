@@ -0,0 +1,153 @@
+//! A JSON dump of every sort, term and theorem of a file.
+//!
+//! The layout of the dump is described by `VERSION`, which is increased on
+//! every change that is not backwards compatible:
+//!
+//! ```text
+//! {
+//!   "format": "trivial-verifier-dump",
+//!   "version": 1,
+//!   "sorts": [{ "index", "name", "pure", "strict", "provable", "free" }],
+//!   "terms": [{ "index", "name", "local", "definition", "binders", "return",
+//!               "value", "dummies" }],
+//!   "theorems": [{ "index", "name", "local", "axiom", "binders",
+//!                  "hypotheses", "conclusion" }]
+//! }
+//! ```
+//!
+//! A binder is `{ "sort", "bound", "deps" }`, where `deps` lists the binder
+//! positions of the bound variables it depends on. An expression is either
+//! `{ "var": n }` with the binder position of the variable, or
+//! `{ "term": id, "args": [...] }`. Definitions without a value and terms have
+//! a `null` value; the dummy variables of a value are numbered after the
+//! binders, and `dummies` lists their sorts. Names are `null` if the file has
+//! no index.
+
+use crate::expr::Expr;
+use crate::kernel::{error::Kind, opcode, KResult, Table, Term, Theorem, Var, Var_};
+use crate::Verifier;
+use serde_json::{json, Value};
+
+pub const FORMAT: &str = "trivial-verifier-dump";
+
+pub const VERSION: u32 = 1;
+
+pub fn expr_to_json(expr: &Expr) -> Value {
+    match expr {
+        Expr::Var(idx) => json!({ "var": idx }),
+        Expr::Term(id, args) => json!({
+            "term": id,
+            "args": args.iter().map(expr_to_json).collect::<Vec<_>>(),
+        }),
+    }
+}
+
+fn binder_to_json(binders: &[Var_], ty: &Var_) -> Value {
+    let deps: Vec<_> = if ty.is_bound() {
+        Vec::new()
+    } else {
+        binders
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| x.is_bound())
+            .enumerate()
+            .filter(|(bit, _)| ty.get_deps() & (1 << bit) != 0)
+            .map(|(_, (idx, _))| idx)
+            .collect()
+    };
+
+    json!({
+        "sort": ty.get_sort_idx(),
+        "bound": ty.is_bound(),
+        "deps": deps,
+    })
+}
+
+/// Dumps sort `idx`, in the format described in the module documentation.
+pub fn dump_sort(verifier: &Verifier, idx: usize) -> Option<Value> {
+    let sort = verifier.table.get_sort(idx as u8)?;
+
+    Some(json!({
+        "index": idx,
+        "name": verifier.get_sort_name(idx),
+        "pure": sort.0 & 1 != 0,
+        "strict": sort.0 & 2 != 0,
+        "provable": sort.0 & 4 != 0,
+        "free": sort.0 & 8 != 0,
+    }))
+}
+
+/// Dumps term `idx`, in the format described in the module documentation.
+pub fn dump_term(verifier: &Verifier, idx: usize) -> KResult<Value> {
+    let table = &verifier.table;
+    let term = table.get_term(idx as u32).ok_or(Kind::InvalidTerm)?;
+    let binders = table
+        .get_binders(term.get_binders())
+        .ok_or(Kind::InvalidBinderIndices)?;
+
+    let (value, dummies) = match verifier.get_definition_value(idx as u32)? {
+        Some((value, dummies)) => (expr_to_json(&value), dummies),
+        None => (Value::Null, Vec::new()),
+    };
+
+    let local = verifier.get_term_info(idx).map(|x| x.is_local());
+
+    Ok(json!({
+        "index": idx,
+        "name": verifier.get_term_name(idx),
+        "local": local.unwrap_or(false),
+        "definition": term.is_definition(),
+        "binders": binders.iter().map(|x| binder_to_json(binders, x)).collect::<Vec<_>>(),
+        "return": binder_to_json(binders, term.get_return_type()),
+        "value": value,
+        "dummies": dummies,
+    }))
+}
+
+/// Dumps theorem `idx`, in the format described in the module documentation.
+pub fn dump_theorem(verifier: &Verifier, idx: usize) -> KResult<Value> {
+    let table = &verifier.table;
+    let thm = table.get_theorem(idx as u32).ok_or(Kind::InvalidTheorem)?;
+    let binders = table
+        .get_binders(thm.get_binders())
+        .ok_or(Kind::InvalidBinderIndices)?;
+
+    let (hyps, conclusion) = verifier.get_theorem_statement(idx as u32)?;
+
+    let kind = verifier.get_theorem_info(idx).map(|x| x.kind);
+
+    Ok(json!({
+        "index": idx,
+        "name": verifier.get_theorem_name(idx),
+        "local": kind == Some(opcode::Statement::LocalTerm),
+        "axiom": kind == Some(opcode::Statement::Axiom),
+        "binders": binders.iter().map(|x| binder_to_json(binders, x)).collect::<Vec<_>>(),
+        "hypotheses": hyps.iter().map(expr_to_json).collect::<Vec<_>>(),
+        "conclusion": expr_to_json(&conclusion),
+    }))
+}
+
+/// Dumps the whole file, in the format described in the module documentation.
+pub fn dump(verifier: &Verifier) -> KResult<Value> {
+    let table = &verifier.table;
+
+    let sorts: Vec<_> = (0..table.sorts.len())
+        .filter_map(|x| dump_sort(verifier, x))
+        .collect();
+
+    let terms = (0..table.terms.len())
+        .map(|x| dump_term(verifier, x))
+        .collect::<KResult<Vec<_>>>()?;
+
+    let theorems = (0..table.theorems.len())
+        .map(|x| dump_theorem(verifier, x))
+        .collect::<KResult<Vec<_>>>()?;
+
+    Ok(json!({
+        "format": FORMAT,
+        "version": VERSION,
+        "sorts": sorts,
+        "terms": terms,
+        "theorems": theorems,
+    }))
+}
@@ -0,0 +1,155 @@
+use crate::kernel::context::store::ElementRef;
+use crate::kernel::context::{PackedPtr, Ptr};
+use crate::kernel::error::Kind;
+use crate::kernel::{KResult, Store};
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// An owned expression tree.
+///
+/// Expressions in a `Store` are only valid until the next statement clears
+/// it, so anything that needs to keep an expression around converts it into
+/// this form first.
+#[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Expr {
+    /// A variable, identified by its position on the proof heap.
+    Var(u16),
+    /// A term application with the index of the term and its arguments.
+    Term(u32, Vec<Expr>),
+}
+
+impl Expr {
+    pub fn from_store<S: Store>(store: &S, ptr: PackedPtr) -> KResult<Expr> {
+        let element = store
+            .get_element(Ptr::from(ptr))
+            .ok_or(Kind::InvalidStoreIndex)?;
+
+        match element {
+            ElementRef::Variable { var, .. } => Ok(Expr::Var(*var)),
+            ElementRef::Term { id, args, .. } => {
+                let args = args
+                    .iter()
+                    .map(|arg| Expr::from_store(store, *arg))
+                    .collect::<KResult<Vec<_>>>()?;
+
+                Ok(Expr::Term(*id, args))
+            }
+            ElementRef::Conv { .. } => Err(Kind::InvalidStoreType),
+        }
+    }
+
+    /// Returns the number of nodes in the expression.
+    pub fn size(&self) -> usize {
+        match self {
+            Expr::Var(_) => 1,
+            Expr::Term(_, args) => 1 + args.iter().map(Expr::size).sum::<usize>(),
+        }
+    }
+
+    /// Returns true if the term `id` occurs anywhere in the expression.
+    pub fn mentions(&self, id: u32) -> bool {
+        match self {
+            Expr::Var(_) => false,
+            Expr::Term(t, args) => *t == id || args.iter().any(|x| x.mentions(id)),
+        }
+    }
+
+    /// Renumbers the variables from `nr_args` on, which are the dummy
+    /// variables of a definition, in order of their first occurrence.
+    ///
+    /// Returns the new expression and the old index of every dummy.
+    pub fn renumber_dummies(&self, nr_args: u16) -> (Expr, Vec<u16>) {
+        fn go(expr: &Expr, nr_args: u16, seen: &mut Vec<u16>) -> Expr {
+            match expr {
+                Expr::Var(x) if *x < nr_args => Expr::Var(*x),
+                Expr::Var(x) => {
+                    let idx = match seen.iter().position(|y| y == x) {
+                        Some(idx) => idx,
+                        None => {
+                            seen.push(*x);
+                            seen.len() - 1
+                        }
+                    };
+
+                    Expr::Var(nr_args + idx as u16)
+                }
+                Expr::Term(id, args) => {
+                    Expr::Term(*id, args.iter().map(|x| go(x, nr_args, seen)).collect())
+                }
+            }
+        }
+
+        let mut seen = Vec::new();
+        let expr = go(self, nr_args, &mut seen);
+
+        (expr, seen)
+    }
+
+    pub fn to_display<'b, F>(&self, names: F) -> DisplayExpr<'_, F>
+    where
+        F: Fn(u32) -> Option<&'b str>,
+    {
+        DisplayExpr(self, names)
+    }
+}
+
+use core::fmt::{self, Display, Formatter};
+
+/// Prints an expression as an s-expression, using `F` to look up term names.
+pub struct DisplayExpr<'a, F>(&'a Expr, F);
+
+impl<'a, 'b, F> DisplayExpr<'a, F>
+where
+    F: Fn(u32) -> Option<&'b str>,
+{
+    fn write(&self, f: &mut Formatter, expr: &Expr) -> fmt::Result {
+        match expr {
+            Expr::Var(idx) => write!(f, "v{}", idx),
+            Expr::Term(id, args) => {
+                if !args.is_empty() {
+                    write!(f, "(")?;
+                }
+
+                match (self.1)(*id) {
+                    Some(name) => write!(f, "{}", name)?,
+                    None => write!(f, "t{}", id)?,
+                }
+
+                for i in args {
+                    write!(f, " ")?;
+                    self.write(f, i)?;
+                }
+
+                if !args.is_empty() {
+                    write!(f, ")")?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl<'a, 'b, F> Display for DisplayExpr<'a, F>
+where
+    F: Fn(u32) -> Option<&'b str>,
+{
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.write(f, self.0)
+    }
+}
+
+/// Shows an entry of a stack or heap of the kernel: an expression, the proof
+/// of an expression marked with `|-`, or a conversion obligation.
+pub fn display_entry<'b, S, F>(store: &S, ptr: PackedPtr, names: F) -> String
+where
+    S: Store,
+    F: Fn(u32) -> Option<&'b str>,
+{
+    match Expr::from_store(store, ptr) {
+        Ok(expr) if ptr.as_proof().is_some() => format!("|- {}", expr.to_display(names)),
+        Ok(expr) => expr.to_display(names).to_string(),
+        Err(_) => ptr.to_display(store).to_string(),
+    }
+}
@@ -0,0 +1,285 @@
+use crate::builder::{MmbBuilder, StatementEntry, TermEntry, TheoremEntry};
+use crate::kernel::opcode::{self, Command};
+use crate::kernel::{Var, Var_};
+use crate::Verifier;
+use alloc::vec;
+use alloc::vec::Vec;
+
+/// The declarations that are kept, as the new index of every old index.
+///
+/// Every function returns `None` for an operand that does not refer to a
+/// kept declaration.
+struct Renumbering {
+    sorts: Vec<Option<u32>>,
+    terms: Vec<Option<u32>>,
+    theorems: Vec<Option<u32>>,
+}
+
+impl Renumbering {
+    fn sort(&self, idx: u32) -> Option<u32> {
+        self.sorts.get(idx as usize).copied().flatten()
+    }
+
+    fn term(&self, idx: u32) -> Option<u32> {
+        self.terms.get(idx as usize).copied().flatten()
+    }
+
+    fn theorem(&self, idx: u32) -> Option<u32> {
+        self.theorems.get(idx as usize).copied().flatten()
+    }
+
+    fn var(&self, var: &Var_) -> Option<Var_> {
+        Some(Var_::new(
+            self.sort(var.get_sort_idx() as u32)? as u8,
+            var.get_deps(),
+            var.is_bound(),
+        ))
+    }
+
+    fn vars(&self, vars: &[Var_]) -> Option<Vec<Var_>> {
+        vars.iter().map(|x| self.var(x)).collect()
+    }
+
+    fn unify(&self, stream: &[Command<opcode::Unify>]) -> Option<Vec<Command<opcode::Unify>>> {
+        use opcode::Unify;
+
+        stream
+            .iter()
+            .map(|x| {
+                let operand = match x.opcode {
+                    Unify::Term | Unify::TermSave => self.term(x.operand)?,
+                    Unify::Dummy => self.sort(x.operand)?,
+                    _ => x.operand,
+                };
+
+                Some(Command {
+                    opcode: x.opcode,
+                    operand,
+                })
+            })
+            .collect()
+    }
+
+    fn proof(&self, stream: &[Command<opcode::Proof>]) -> Option<Vec<Command<opcode::Proof>>> {
+        use opcode::Proof;
+
+        stream
+            .iter()
+            .map(|x| {
+                let operand = match x.opcode {
+                    Proof::Term | Proof::TermSave => self.term(x.operand)?,
+                    Proof::Thm | Proof::ThmSave => self.theorem(x.operand)?,
+                    Proof::Dummy => self.sort(x.operand)?,
+                    _ => x.operand,
+                };
+
+                Some(Command {
+                    opcode: x.opcode,
+                    operand,
+                })
+            })
+            .collect()
+    }
+}
+
+struct Closure {
+    sorts: Vec<bool>,
+    terms: Vec<bool>,
+    theorems: Vec<bool>,
+    todo: Vec<Item>,
+}
+
+#[derive(Copy, Clone)]
+enum Item {
+    Term(usize),
+    Theorem(usize),
+}
+
+impl Closure {
+    fn sort(&mut self, idx: usize) {
+        if let Some(x) = self.sorts.get_mut(idx) {
+            *x = true;
+        }
+    }
+
+    fn term(&mut self, idx: usize) {
+        if let Some(x) = self.terms.get_mut(idx) {
+            if !*x {
+                *x = true;
+                self.todo.push(Item::Term(idx));
+            }
+        }
+    }
+
+    fn theorem(&mut self, idx: usize) {
+        if let Some(x) = self.theorems.get_mut(idx) {
+            if !*x {
+                *x = true;
+                self.todo.push(Item::Theorem(idx));
+            }
+        }
+    }
+
+    fn unify(&mut self, stream: &[Command<opcode::Unify>]) {
+        use opcode::Unify;
+
+        for i in stream {
+            match i.opcode {
+                Unify::Term | Unify::TermSave => self.term(i.operand as usize),
+                Unify::Dummy => self.sort(i.operand as usize),
+                _ => {}
+            }
+        }
+    }
+
+    fn proof(&mut self, stream: &[Command<opcode::Proof>]) {
+        use opcode::Proof;
+
+        for i in stream {
+            match i.opcode {
+                Proof::Term | Proof::TermSave => self.term(i.operand as usize),
+                Proof::Thm | Proof::ThmSave => self.theorem(i.operand as usize),
+                Proof::Dummy => self.sort(i.operand as usize),
+                _ => {}
+            }
+        }
+    }
+}
+
+fn renumber(keep: &[bool]) -> Vec<Option<u32>> {
+    let mut next = 0;
+
+    keep.iter()
+        .map(|x| {
+            if *x {
+                next += 1;
+                Some(next - 1)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// Cuts a file down to one theorem and everything its statement and proof
+/// depend on, transitively.
+///
+/// The order of the statements is kept, and all sorts, terms and theorems
+/// are renumbered. Returns `None` if the theorem does not exist, a proof
+/// stream is not available, or a stream refers to a declaration that does
+/// not exist.
+pub fn extract(verifier: &Verifier, theorem: u32) -> Option<MmbBuilder> {
+    let full = MmbBuilder::from_verifier(verifier)?;
+    full.theorems.get(theorem as usize)?;
+
+    let statements = verifier.get_statements();
+    let mut term_proofs = Vec::with_capacity(full.terms.len());
+    let mut theorem_proofs = Vec::with_capacity(full.theorems.len());
+
+    for (i, statement) in statements.iter().enumerate() {
+        if statement.is_term() {
+            term_proofs.push(i);
+        } else if statement.is_theorem() {
+            theorem_proofs.push(i);
+        }
+    }
+
+    let mut closure = Closure {
+        sorts: vec![false; full.sorts.len()],
+        terms: vec![false; full.terms.len()],
+        theorems: vec![false; full.theorems.len()],
+        todo: Vec::new(),
+    };
+
+    closure.theorem(theorem as usize);
+
+    while let Some(item) = closure.todo.pop() {
+        let (binders, unify, statement) = match item {
+            Item::Term(idx) => {
+                let term = &full.terms[idx];
+                closure.sort((term.sort & 0x7F) as usize);
+                closure.sort(term.ret.get_sort_idx() as usize);
+
+                (&term.binders, &term.unify, term_proofs.get(idx))
+            }
+            Item::Theorem(idx) => {
+                let thm = &full.theorems[idx];
+
+                (&thm.binders, &thm.unify, theorem_proofs.get(idx))
+            }
+        };
+
+        for i in binders {
+            closure.sort(i.get_sort_idx() as usize);
+        }
+
+        closure.unify(unify);
+
+        let proof = statement
+            .and_then(|x| full.statements.get(*x))
+            .and_then(|x| x.proof.as_deref());
+
+        if let Some(proof) = proof {
+            closure.proof(proof);
+        }
+    }
+
+    let map = Renumbering {
+        sorts: renumber(&closure.sorts),
+        terms: renumber(&closure.terms),
+        theorems: renumber(&closure.theorems),
+    };
+
+    let mut builder = MmbBuilder::new();
+
+    for (i, statement) in full.statements.iter().enumerate() {
+        let info = statements[i];
+        let idx = info.index;
+
+        let keep = if info.is_sort() {
+            closure.sorts[idx]
+        } else if info.is_term() {
+            closure.terms[idx]
+        } else {
+            closure.theorems[idx]
+        };
+
+        if !keep {
+            continue;
+        }
+
+        if info.is_sort() {
+            builder.sorts.push(full.sorts[idx].clone());
+        } else if info.is_term() {
+            let term = &full.terms[idx];
+
+            builder.terms.push(TermEntry {
+                name: term.name.clone(),
+                sort: map.sort((term.sort & 0x7F) as u32)? as u8 | (term.sort & 0x80),
+                binders: map.vars(&term.binders)?,
+                ret: map.var(&term.ret)?,
+                unify: map.unify(&term.unify)?,
+            });
+        } else {
+            let thm = &full.theorems[idx];
+
+            builder.theorems.push(TheoremEntry {
+                name: thm.name.clone(),
+                binders: map.vars(&thm.binders)?,
+                unify: map.unify(&thm.unify)?,
+            });
+        }
+
+        let proof = match &statement.proof {
+            Some(x) => Some(map.proof(x)?),
+            None => None,
+        };
+
+        builder.statements.push(StatementEntry {
+            code: statement.code,
+            proof,
+        });
+    }
+
+    Some(builder)
+}
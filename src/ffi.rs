@@ -0,0 +1,384 @@
+//! A C interface, built with `--features ffi`, which also writes the header
+//! `include/trivial_verifier.h`.
+//!
+//! A verifier is an opaque handle that is created from a buffer with
+//! `trivial_verifier_new` and released with `trivial_verifier_free`. Every
+//! fallible function returns a `TrivialStatus`, which names the error kind of
+//! the kernel if verification failed.
+
+use crate::kernel::error::Kind;
+use crate::Verifier;
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// The outcome of a call, `TRIVIAL_STATUS_OK` on success and the error kind
+/// of the kernel if verification failed.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TrivialStatus {
+    Ok = 0,
+    /// There was nothing left to verify.
+    Finished = 1,
+    /// The buffer is not a valid file.
+    InvalidFile = 2,
+    /// A pointer argument was null or a string was not valid UTF-8.
+    InvalidArgument = 3,
+    /// The verifier panicked, after which the handle can only be freed.
+    Panicked = 4,
+    InvalidHeapIndex = 16,
+    InvalidProofIndex = 17,
+    InvalidTheorem = 18,
+    InvalidStoreIndex = 19,
+    InvalidStoreType = 20,
+    InvalidTerm = 21,
+    InvalidStoreExpr = 22,
+    InvalidSort = 23,
+    InvalidBinderIndices = 24,
+    InvalidUnifyCommandIndex = 25,
+    InvalidStackType = 26,
+    IncompatibleTypes = 27,
+    DependencyOverflow = 28,
+    UnifyStackUnderflow = 29,
+    CantSaveConvertabilityObligation = 30,
+    UnifyRefFailure = 31,
+    UnifyTermFailure = 32,
+    ProofStackUnderflow = 33,
+    SortNotProvable = 34,
+    SortIsStrict = 35,
+    SortIsPure = 36,
+    StackHasMoreThanOne = 37,
+    UnaccountedDependencies = 38,
+    BadReturnType = 39,
+    TypeError = 40,
+    TooManyBoundVariables = 41,
+    HypStackUnderflow = 42,
+    DummyCommandInTheorem = 43,
+    CongUnifyError = 44,
+    BindDep = 45,
+    DisjointVariableViolation = 46,
+    UnknownCommand = 47,
+    UnfinishedHypStack = 48,
+    UnfinishedUnifyStack = 49,
+    HypInDefStatement = 50,
+    InvalidOpcodeInDef = 51,
+    Impossible = 52,
+    TheoremOutOfRange = 53,
+    TermOutOfRange = 54,
+    SortOutOfRange = 55,
+    StreamExhausted = 56,
+    MissingProofStream = 57,
+}
+
+// the kernel error kinds, which have a status of the same name
+macro_rules! kinds {
+    ($($kind:ident,)*) => {
+        impl From<Kind> for TrivialStatus {
+            fn from(kind: Kind) -> TrivialStatus {
+                match kind {
+                    $(Kind::$kind => TrivialStatus::$kind,)*
+                }
+            }
+        }
+
+        impl TrivialStatus {
+            fn message(self) -> &'static [u8] {
+                match self {
+                    TrivialStatus::Ok => b"Ok\0",
+                    TrivialStatus::Finished => b"Finished\0",
+                    TrivialStatus::InvalidFile => b"InvalidFile\0",
+                    TrivialStatus::InvalidArgument => b"InvalidArgument\0",
+                    TrivialStatus::Panicked => b"Panicked\0",
+                    $(TrivialStatus::$kind => concat!(stringify!($kind), "\0").as_bytes(),)*
+                }
+            }
+        }
+    };
+}
+
+kinds! {
+    InvalidHeapIndex,
+    InvalidProofIndex,
+    InvalidTheorem,
+    InvalidStoreIndex,
+    InvalidStoreType,
+    InvalidTerm,
+    InvalidStoreExpr,
+    InvalidSort,
+    InvalidBinderIndices,
+    InvalidUnifyCommandIndex,
+    InvalidStackType,
+    IncompatibleTypes,
+    DependencyOverflow,
+    UnifyStackUnderflow,
+    CantSaveConvertabilityObligation,
+    UnifyRefFailure,
+    UnifyTermFailure,
+    ProofStackUnderflow,
+    SortNotProvable,
+    SortIsStrict,
+    SortIsPure,
+    StackHasMoreThanOne,
+    UnaccountedDependencies,
+    BadReturnType,
+    TypeError,
+    TooManyBoundVariables,
+    HypStackUnderflow,
+    DummyCommandInTheorem,
+    CongUnifyError,
+    BindDep,
+    DisjointVariableViolation,
+    UnknownCommand,
+    UnfinishedHypStack,
+    UnfinishedUnifyStack,
+    HypInDefStatement,
+    InvalidOpcodeInDef,
+    Impossible,
+    TheoremOutOfRange,
+    TermOutOfRange,
+    SortOutOfRange,
+    StreamExhausted,
+    MissingProofStream,
+}
+
+/// An opaque handle to a verifier.
+pub struct TrivialVerifier(Verifier);
+
+/// Returns the name of a status as a static string.
+#[no_mangle]
+pub extern "C" fn trivial_status_message(status: TrivialStatus) -> *const c_char {
+    status.message().as_ptr() as *const c_char
+}
+
+/// Creates a verifier from a copy of `len` bytes at `data`, or returns null
+/// if they are not a valid file. A file without an index has no names.
+///
+/// # Safety
+///
+/// `data` has to point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_new(data: *const u8, len: usize) -> *mut TrivialVerifier {
+    if data.is_null() {
+        return std::ptr::null_mut();
+    }
+
+    let data = std::slice::from_raw_parts(data, len);
+
+    // a panic must not unwind into the caller
+    match catch_unwind(|| Verifier::new(data)) {
+        Ok(Some(verifier)) => Box::into_raw(Box::new(TrivialVerifier(verifier))),
+        _ => std::ptr::null_mut(),
+    }
+}
+
+/// Releases a verifier, null is ignored.
+///
+/// # Safety
+///
+/// `verifier` has to be null or returned by `trivial_verifier_new`, and is
+/// invalid afterwards.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_free(verifier: *mut TrivialVerifier) {
+    if !verifier.is_null() {
+        drop(Box::from_raw(verifier));
+    }
+}
+
+/// Verifies the remaining statements.
+///
+/// # Safety
+///
+/// `verifier` has to be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_run(verifier: *mut TrivialVerifier) -> TrivialStatus {
+    match verifier.as_mut() {
+        Some(verifier) => match catch_unwind(AssertUnwindSafe(|| verifier.0.run(&mut |_, _| {}))) {
+            Ok(Ok(())) => TrivialStatus::Ok,
+            Ok(Err(e)) => e.into(),
+            Err(_) => TrivialStatus::Panicked,
+        },
+        None => TrivialStatus::InvalidArgument,
+    }
+}
+
+/// Takes one step, which returns `TRIVIAL_STATUS_FINISHED` if there was
+/// nothing left to verify.
+///
+/// # Safety
+///
+/// `verifier` has to be null or a valid handle.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_step(verifier: *mut TrivialVerifier) -> TrivialStatus {
+    match verifier.as_mut() {
+        Some(verifier) => {
+            match catch_unwind(AssertUnwindSafe(|| verifier.0.step(&mut |_, _| {}))) {
+                Ok(Ok(Some(()))) => TrivialStatus::Ok,
+                Ok(Ok(None)) => TrivialStatus::Finished,
+                Ok(Err(e)) => e.into(),
+                Err(_) => TrivialStatus::Panicked,
+            }
+        }
+        None => TrivialStatus::InvalidArgument,
+    }
+}
+
+/// The number of sorts, terms and theorems of the file.
+///
+/// # Safety
+///
+/// `verifier` has to be a valid handle, and the pointers null or writable.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_counts(
+    verifier: *const TrivialVerifier,
+    sorts: *mut usize,
+    terms: *mut usize,
+    theorems: *mut usize,
+) -> TrivialStatus {
+    let table = match verifier.as_ref() {
+        Some(verifier) => &verifier.0.table,
+        None => return TrivialStatus::InvalidArgument,
+    };
+
+    for (ptr, count) in [
+        (sorts, table.sorts.len()),
+        (terms, table.terms.len()),
+        (theorems, table.theorems.len()),
+    ]
+    .iter()
+    {
+        if let Some(x) = ptr.as_mut() {
+            *x = *count;
+        }
+    }
+
+    TrivialStatus::Ok
+}
+
+/// Copies `name` to `buf` like `snprintf`, and returns its length without
+/// the terminator, or -1 if there is no name.
+unsafe fn copy_name(name: Option<&str>, buf: *mut c_char, len: usize) -> isize {
+    let name = match name {
+        Some(name) => name.as_bytes(),
+        None => return -1,
+    };
+
+    if !buf.is_null() && len > 0 {
+        let n = name.len().min(len - 1);
+        std::ptr::copy_nonoverlapping(name.as_ptr(), buf as *mut u8, n);
+        *buf.add(n) = 0;
+    }
+
+    name.len() as isize
+}
+
+unsafe fn to_str<'a>(name: *const c_char) -> Option<&'a str> {
+    if name.is_null() {
+        None
+    } else {
+        CStr::from_ptr(name).to_str().ok()
+    }
+}
+
+/// Writes the name of sort `idx` to `buf`, see `trivial_verifier_theorem_name`.
+///
+/// # Safety
+///
+/// `verifier` has to be a valid handle, and `buf` null or `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_sort_name(
+    verifier: *const TrivialVerifier,
+    idx: usize,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let name = verifier.as_ref().and_then(|x| x.0.get_sort_name(idx));
+
+    copy_name(name, buf, len)
+}
+
+/// Writes the name of term `idx` to `buf`, see `trivial_verifier_theorem_name`.
+///
+/// # Safety
+///
+/// `verifier` has to be a valid handle, and `buf` null or `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_term_name(
+    verifier: *const TrivialVerifier,
+    idx: usize,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let name = verifier.as_ref().and_then(|x| x.0.get_term_name(idx));
+
+    copy_name(name, buf, len)
+}
+
+/// Writes the NUL terminated name of theorem `idx` to `buf`, truncated to
+/// `len` bytes. Returns the length of the whole name, or -1 if there is no
+/// such theorem or it has no name.
+///
+/// # Safety
+///
+/// `verifier` has to be a valid handle, and `buf` null or `len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_theorem_name(
+    verifier: *const TrivialVerifier,
+    idx: usize,
+    buf: *mut c_char,
+    len: usize,
+) -> isize {
+    let name = verifier.as_ref().and_then(|x| x.0.get_theorem_name(idx));
+
+    copy_name(name, buf, len)
+}
+
+/// Returns the index of the sort `name`, or -1 if there is none.
+///
+/// # Safety
+///
+/// `verifier` has to be a valid handle, and `name` a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_sort_index(
+    verifier: *const TrivialVerifier,
+    name: *const c_char,
+) -> isize {
+    verifier
+        .as_ref()
+        .zip(to_str(name))
+        .and_then(|(x, name)| x.0.get_sort_index(name))
+        .map_or(-1, |x| x as isize)
+}
+
+/// Returns the index of the term `name`, or -1 if there is none.
+///
+/// # Safety
+///
+/// `verifier` has to be a valid handle, and `name` a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_term_index(
+    verifier: *const TrivialVerifier,
+    name: *const c_char,
+) -> isize {
+    verifier
+        .as_ref()
+        .zip(to_str(name))
+        .and_then(|(x, name)| x.0.get_term_index(name))
+        .map_or(-1, |x| x as isize)
+}
+
+/// Returns the index of the theorem `name`, or -1 if there is none.
+///
+/// # Safety
+///
+/// `verifier` has to be a valid handle, and `name` a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn trivial_verifier_theorem_index(
+    verifier: *const TrivialVerifier,
+    name: *const c_char,
+) -> isize {
+    verifier
+        .as_ref()
+        .zip(to_str(name))
+        .and_then(|(x, name)| x.0.get_theorem_index(name))
+        .map_or(-1, |x| x as isize)
+}
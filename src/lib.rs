@@ -1,7 +1,40 @@
+//! The verifiers, the builder, extraction and proof traces only use `core`
+//! and `alloc`, but the crate needs `std`: the kernel, the parser and
+//! `mmb-types` do not declare `no_std`, and the parser enables the `std`
+//! feature of `nom`, so targets such as `thumbv7em-none-eabihf` are not
+//! supported.
+
+extern crate alloc;
+
+pub mod builder;
+pub mod cache;
+pub mod certificate;
+pub mod diff;
+pub mod docs;
+pub mod dump;
+pub mod expr;
+pub mod extract;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod lsp;
+pub mod mm0;
 pub mod mmb_visitor;
+mod one_shot;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "repl")]
+pub mod repl;
+pub mod search;
+pub mod server;
 mod statement_iter;
+pub mod trace;
+#[cfg(feature = "tui")]
+pub mod tui;
 pub mod verifier;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
+pub use one_shot::{OneShotVerifier, ZeroCopyVerifier};
 pub use verifier::Verifier;
 
 pub use trivial_kernel as kernel;
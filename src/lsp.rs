@@ -0,0 +1,437 @@
+//! A language server for specification files.
+//!
+//! When a `.mm0` file is opened or saved, the `.mmb` file of the same name
+//! next to it is verified. Statements whose proofs fail are reported on
+//! their name in the specification, and so are the theorems whose proofs use
+//! a failing theorem. Hovering over a theorem shows its statement as the
+//! kernel sees it, and the axioms its proof depends on.
+//!
+//! Messages are framed as in `server`, and the text of a document is synced
+//! in full.
+
+use crate::kernel::error::Kind;
+use crate::kernel::opcode;
+use crate::mm0::{self, Mm0, Statement};
+use crate::server::{Handler, RpcError, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND};
+use crate::verifier::StatementInfo;
+use crate::Verifier;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+const ERROR: u32 = 1;
+const WARNING: u32 = 2;
+
+/// The verified proofs of a specification.
+struct Proofs {
+    verifier: Verifier,
+    failures: Vec<(StatementInfo, Kind)>,
+}
+
+impl Proofs {
+    fn load(path: PathBuf) -> Result<Proofs, String> {
+        let data = std::fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let mut verifier = Verifier::new(&data)
+            .ok_or_else(|| format!("{}: not a valid mmb file", path.display()))?;
+
+        let failures = verifier.find_failures();
+
+        Ok(Proofs { verifier, failures })
+    }
+
+    /// The failure of every theorem whose proof fails, or uses a theorem that
+    /// fails, with the name of the theorem that fails.
+    fn broken_theorems(&self) -> HashMap<usize, (Option<&str>, Kind)> {
+        let verifier = &self.verifier;
+        let mut broken = HashMap::new();
+
+        for (i, statement) in verifier.get_statements().iter().enumerate() {
+            if !statement.is_theorem() {
+                continue;
+            }
+
+            let failure = self.failures.iter().find(|(x, _)| x == statement);
+
+            if let Some((_, kind)) = failure {
+                broken.insert(
+                    statement.index,
+                    (verifier.get_statement_name(statement), *kind),
+                );
+                continue;
+            }
+
+            let used = verifier
+                .get_proof(i)
+                .unwrap_or_default()
+                .iter()
+                .filter(|x| matches!(x.opcode, opcode::Proof::Thm | opcode::Proof::ThmSave))
+                .find_map(|x| broken.get(&(x.operand as usize)).copied());
+
+            if let Some(used) = used {
+                broken.insert(statement.index, used);
+            }
+        }
+
+        broken
+    }
+}
+
+struct Document {
+    text: String,
+    proofs: Result<Proofs, String>,
+}
+
+/// Converts a byte offset into an LSP position, which counts UTF-16 units.
+fn to_position(text: &str, offset: usize) -> Value {
+    let offset = offset.min(text.len());
+    let (line, col) = mm0::position(text, offset);
+    let character = text[offset - col..offset].encode_utf16().count();
+
+    json!({ "line": line, "character": character })
+}
+
+/// Converts an LSP position into a byte offset.
+fn to_offset(text: &str, position: &Value) -> Option<usize> {
+    let line = position["line"].as_u64()? as usize;
+    let character = position["character"].as_u64()? as usize;
+
+    let start = if line == 0 {
+        0
+    } else {
+        text.match_indices('\n').nth(line - 1)?.0 + 1
+    };
+
+    let mut units = 0;
+
+    for (i, c) in text[start..].char_indices() {
+        if units >= character || c == '\n' {
+            return Some(start + i);
+        }
+
+        units += c.len_utf16();
+    }
+
+    Some(text.len())
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// The range of the name of a statement that starts at `offset`.
+fn name_range(text: &str, offset: usize, name: &str) -> Value {
+    let rest = &text[offset.min(text.len())..];
+
+    let start = rest
+        .match_indices(name)
+        .map(|(i, _)| offset + i)
+        .find(|&i| {
+            let before = text[..i].chars().next_back();
+            let after = text[i + name.len()..].chars().next();
+
+            !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+        })
+        .unwrap_or(offset);
+
+    json!({
+        "start": to_position(text, start),
+        "end": to_position(text, start + name.len()),
+    })
+}
+
+fn diagnostic(range: Value, severity: u32, message: &str) -> Value {
+    json!({
+        "range": range,
+        "severity": severity,
+        "source": "trivial-verifier",
+        "message": message,
+    })
+}
+
+fn diagnostics(document: &Document) -> Vec<Value> {
+    let text = &document.text;
+    let start = json!({ "start": to_position(text, 0), "end": to_position(text, 0) });
+
+    let mm0 = match Mm0::parse(text) {
+        Ok(mm0) => mm0,
+        Err(e) => {
+            let at = to_position(text, e.offset);
+            let range = json!({ "start": at, "end": at });
+
+            return vec![diagnostic(range, ERROR, &format!("{:?}", e.kind))];
+        }
+    };
+
+    let proofs = match &document.proofs {
+        Ok(proofs) => proofs,
+        Err(e) => return vec![diagnostic(start, WARNING, e)],
+    };
+
+    let verifier = &proofs.verifier;
+    let mut diagnostics = Vec::new();
+
+    // sorts and terms that fail, theorems are handled below
+    for (statement, kind) in proofs.failures.iter().filter(|(x, _)| !x.is_theorem()) {
+        let name = verifier.get_statement_name(statement);
+
+        if let Some(x) = name.and_then(|x| mm0.get_statement(x)) {
+            let range = name_range(text, x.offset(), x.name());
+            diagnostics.push(diagnostic(range, ERROR, &format!("fails: {:?}", kind)));
+        }
+    }
+
+    let broken = proofs.broken_theorems();
+
+    for i in &mm0.statements {
+        if let Statement::Assert(x) = i {
+            let idx = verifier.get_theorem_index(&x.name);
+
+            if let Some((name, kind)) = idx.and_then(|x| broken.get(&x)) {
+                let range = name_range(text, x.offset, &x.name);
+
+                if *name == Some(x.name.as_str()) {
+                    diagnostics.push(diagnostic(range, ERROR, &format!("fails: {:?}", kind)));
+                } else {
+                    let message = format!("uses {}, which fails: {:?}", name.unwrap_or("?"), kind);
+                    diagnostics.push(diagnostic(range, WARNING, &message));
+                }
+            }
+        }
+    }
+
+    if let Err(e) = mm0::check(verifier, &mm0) {
+        let range = match e.offset {
+            Some(offset) => name_range(text, offset, &e.name),
+            None => start,
+        };
+
+        diagnostics.push(diagnostic(range, ERROR, &e.to_string()));
+    }
+
+    diagnostics
+}
+
+fn hover(document: &Document, offset: usize) -> Option<String> {
+    let mm0 = Mm0::parse(&document.text).ok()?;
+    let proofs = document.proofs.as_ref().ok()?;
+    let verifier = &proofs.verifier;
+
+    let statement = mm0
+        .statements
+        .iter()
+        .take_while(|x| x.offset() <= offset)
+        .last()?;
+
+    let assert = match statement {
+        Statement::Assert(x) => x,
+        _ => return None,
+    };
+
+    let idx = verifier.get_theorem_index(&assert.name)?;
+    let (hyps, conclusion) = verifier.get_theorem_statement(idx as u32).ok()?;
+    let names = |x: u32| verifier.get_term_name(x as usize);
+
+    let keyword = if assert.axiom { "axiom" } else { "theorem" };
+    let mut value = format!("```\n{} {}\n", keyword, assert.name);
+
+    for i in &hyps {
+        value += &format!("  hypothesis {}\n", i.to_display(names));
+    }
+
+    value += &format!("  conclusion {}\n```\n", conclusion.to_display(names));
+
+    let axioms: Vec<_> = verifier
+        .get_axioms(idx)
+        .iter()
+        .map(|x| format!("`{}`", verifier.get_theorem_name(*x).unwrap_or("?")))
+        .collect();
+
+    if !assert.axiom {
+        if axioms.is_empty() {
+            value += "\nuses no axioms";
+        } else {
+            value += &format!("\naxioms: {}", axioms.join(", "));
+        }
+    }
+
+    Some(value)
+}
+
+/// Converts a `file://` uri into a path.
+fn to_path(uri: &str) -> Option<PathBuf> {
+    let path = uri.strip_prefix("file://")?.as_bytes();
+    let mut bytes = Vec::new();
+    let mut i = 0;
+
+    while i < path.len() {
+        let escaped = path
+            .get(i + 1..i + 3)
+            .filter(|_| path[i] == b'%')
+            .and_then(|x| std::str::from_utf8(x).ok())
+            .and_then(|x| u8::from_str_radix(x, 16).ok());
+
+        match escaped {
+            Some(x) => {
+                bytes.push(x);
+                i += 3;
+            }
+            None => {
+                bytes.push(path[i]);
+                i += 1;
+            }
+        }
+    }
+
+    String::from_utf8(bytes).ok().map(PathBuf::from)
+}
+
+#[derive(Default)]
+pub struct LanguageServer {
+    documents: HashMap<String, Document>,
+    shutdown: bool,
+    exit: bool,
+}
+
+impl LanguageServer {
+    pub fn new() -> LanguageServer {
+        LanguageServer::default()
+    }
+
+    fn uri(params: &Value) -> Result<&str, RpcError> {
+        params["textDocument"]["uri"]
+            .as_str()
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "expected a text document"))
+    }
+
+    fn open(&mut self, uri: &str, text: String, notify: &mut dyn FnMut(Value)) {
+        let proofs = match to_path(uri) {
+            Some(path) => Proofs::load(path.with_extension("mmb")),
+            None => Err(format!("{}: not a file", uri)),
+        };
+
+        self.documents
+            .insert(uri.to_string(), Document { text, proofs });
+
+        self.publish(uri, notify);
+    }
+
+    fn publish(&self, uri: &str, notify: &mut dyn FnMut(Value)) {
+        let diagnostics = self.documents.get(uri).map(diagnostics).unwrap_or_default();
+
+        notify(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": diagnostics },
+        }));
+    }
+
+    fn notification(
+        &mut self,
+        method: &str,
+        params: &Value,
+        notify: &mut dyn FnMut(Value),
+    ) -> Result<(), RpcError> {
+        match method {
+            "exit" => self.exit = true,
+            "textDocument/didOpen" => {
+                let uri = LanguageServer::uri(params)?;
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+
+                self.open(uri, text.to_string(), notify);
+            }
+            "textDocument/didChange" => {
+                let uri = LanguageServer::uri(params)?;
+                let changes = params["contentChanges"].as_array();
+                let text = changes.and_then(|x| x.last()?["text"].as_str());
+
+                if let (Some(document), Some(text)) = (self.documents.get_mut(uri), text) {
+                    document.text = text.to_string();
+                    self.publish(uri, notify);
+                }
+            }
+            "textDocument/didSave" => {
+                // the proofs may have been compiled again
+                let uri = LanguageServer::uri(params)?;
+
+                if let Some(document) = self.documents.remove(uri) {
+                    self.open(uri, document.text, notify);
+                }
+            }
+            "textDocument/didClose" => {
+                let uri = LanguageServer::uri(params)?;
+
+                self.documents.remove(uri);
+                self.publish(uri, notify);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, RpcError> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": { "openClose": true, "change": 1, "save": true },
+                    "hoverProvider": true,
+                },
+                "serverInfo": {
+                    "name": "trivial-verifier",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => {
+                let uri = LanguageServer::uri(params)?;
+                let document = match self.documents.get(uri) {
+                    Some(document) => document,
+                    None => return Ok(Value::Null),
+                };
+
+                let offset = to_offset(&document.text, &params["position"])
+                    .ok_or_else(|| RpcError::new(INVALID_PARAMS, "expected a position"))?;
+
+                Ok(match hover(document, offset) {
+                    Some(value) => json!({ "contents": { "kind": "markdown", "value": value } }),
+                    None => Value::Null,
+                })
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, method)),
+        }
+    }
+}
+
+impl Handler for LanguageServer {
+    fn handle(&mut self, message: &Value, notify: &mut dyn FnMut(Value)) -> Option<Value> {
+        let method = message["method"].as_str();
+        let params = &message["params"];
+
+        let id = match message.get("id") {
+            Some(id) => id,
+            None => {
+                // errors in notifications cannot be answered
+                let _ = method.map(|x| self.notification(x, params, notify));
+                return None;
+            }
+        };
+
+        let result = match method {
+            Some(_) if self.shutdown => Err(RpcError::new(INVALID_REQUEST, "shut down")),
+            Some(method) => self.request(method, params),
+            None => Err(RpcError::new(INVALID_REQUEST, "missing method")),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({ "jsonrpc": "2.0", "id": id, "error": e.to_json() }),
+        })
+    }
+
+    fn is_done(&self) -> bool {
+        self.exit
+    }
+}
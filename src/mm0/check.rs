@@ -0,0 +1,306 @@
+use super::{Binder, Env, MathError, Mm0, Statement};
+use crate::expr::Expr;
+use crate::kernel::{opcode, Sort_, Table, Term, Theorem, Var, Var_};
+use crate::Verifier;
+use core::fmt::{self, Display, Formatter};
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum MismatchKind {
+    /// The statement is in the specification, but not in the file.
+    Missing,
+    /// The file exports a statement that is not in the specification.
+    Unexpected,
+    /// The file names the statement differently.
+    Name(String),
+    Kind,
+    SortModifiers,
+    Binders,
+    ReturnType,
+    Definition,
+    Value,
+    Hypotheses,
+    Conclusion,
+    Formula(MathError),
+}
+
+/// The first difference between a specification and a file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Mismatch {
+    pub name: String,
+    /// The offset of the statement in the specification, if it has one.
+    pub offset: Option<usize>,
+    pub kind: MismatchKind,
+}
+
+impl Display for Mismatch {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match &self.kind {
+            MismatchKind::Missing => write!(f, "{}: missing from the mmb file", self.name),
+            MismatchKind::Unexpected => {
+                write!(
+                    f,
+                    "{}: exported by the mmb file, but not specified",
+                    self.name
+                )
+            }
+            MismatchKind::Name(found) => {
+                write!(f, "{}: named {} in the mmb file", self.name, found)
+            }
+            MismatchKind::Kind => write!(f, "{}: statement kinds differ", self.name),
+            MismatchKind::SortModifiers => write!(f, "{}: sort modifiers differ", self.name),
+            MismatchKind::Binders => write!(f, "{}: binders differ", self.name),
+            MismatchKind::ReturnType => write!(f, "{}: return types differ", self.name),
+            MismatchKind::Definition => {
+                write!(f, "{}: term and definition are mixed up", self.name)
+            }
+            MismatchKind::Value => write!(f, "{}: definition values differ", self.name),
+            MismatchKind::Hypotheses => write!(f, "{}: hypotheses differ", self.name),
+            MismatchKind::Conclusion => write!(f, "{}: conclusions differ", self.name),
+            MismatchKind::Formula(e) => write!(f, "{}: invalid formula: {:?}", self.name, e),
+        }
+    }
+}
+
+struct Checker<'a> {
+    verifier: &'a Verifier,
+    env: Env<'a>,
+    sorts: Vec<u8>,
+    terms: Vec<u32>,
+}
+
+/// Checks that the file exports exactly the statements of the specification,
+/// in the same order and with the same binders and statements.
+///
+/// Only the exported statements are compared, so this should be called on a
+/// file that has been verified already.
+pub fn check(verifier: &Verifier, mm0: &Mm0) -> Result<(), Mismatch> {
+    let env = Env::new(mm0).map_err(|e| Mismatch {
+        name: String::new(),
+        offset: None,
+        kind: MismatchKind::Formula(e),
+    })?;
+
+    let mut checker = Checker {
+        verifier,
+        env,
+        sorts: Vec::new(),
+        terms: Vec::new(),
+    };
+
+    let public = verifier.get_public_statements();
+
+    for (i, statement) in mm0.statements.iter().enumerate() {
+        let mismatch = |kind| Mismatch {
+            name: statement.name().to_string(),
+            offset: Some(statement.offset()),
+            kind,
+        };
+
+        let info = public
+            .get(i)
+            .ok_or_else(|| mismatch(MismatchKind::Missing))?;
+
+        let found = if info.is_sort() {
+            verifier.get_sort_name(info.index)
+        } else if info.is_term() {
+            verifier.get_term_name(info.index)
+        } else {
+            verifier.get_theorem_name(info.index)
+        };
+
+        if let Some(found) = found {
+            if found != statement.name() {
+                return Err(mismatch(MismatchKind::Name(found.to_string())));
+            }
+        }
+
+        checker
+            .statement(statement, info.kind, info.index)
+            .map_err(mismatch)?;
+    }
+
+    if let Some(info) = public.get(mm0.statements.len()) {
+        let name = if info.is_sort() {
+            verifier.get_sort_name(info.index)
+        } else if info.is_term() {
+            verifier.get_term_name(info.index)
+        } else {
+            verifier.get_theorem_name(info.index)
+        };
+
+        return Err(Mismatch {
+            name: name.unwrap_or("<unnamed>").to_string(),
+            offset: None,
+            kind: MismatchKind::Unexpected,
+        });
+    }
+
+    Ok(())
+}
+
+impl<'a> Checker<'a> {
+    fn statement(
+        &mut self,
+        statement: &Statement,
+        kind: opcode::Statement,
+        idx: usize,
+    ) -> Result<(), MismatchKind> {
+        let table = &self.verifier.table;
+
+        match (statement, kind) {
+            (Statement::Sort(sort), opcode::Statement::Sort) => {
+                let found: Sort_ = *table.get_sort(idx as u8).ok_or(MismatchKind::Missing)?;
+
+                if found.0 != sort.modifiers() {
+                    return Err(MismatchKind::SortModifiers);
+                }
+
+                self.sorts.push(idx as u8);
+            }
+            (Statement::Term(term), opcode::Statement::TermDef) => {
+                let found = table.get_term(idx as u32).ok_or(MismatchKind::Missing)?;
+                let binders = table
+                    .get_binders(found.get_binders())
+                    .ok_or(MismatchKind::Binders)?;
+
+                if found.is_definition() != term.def.is_some() {
+                    return Err(MismatchKind::Definition);
+                }
+
+                if binders != self.binders(&term.binders)?.as_slice() {
+                    return Err(MismatchKind::Binders);
+                }
+
+                if *found.get_return_type() != self.binder(&term.ret, &term.binders)? {
+                    return Err(MismatchKind::ReturnType);
+                }
+
+                self.terms.push(idx as u32);
+
+                if let Some(Some(value)) = &term.def {
+                    let mut vars = term.binders.clone();
+                    vars.extend(term.dummies.iter().cloned());
+
+                    let sort = self
+                        .env
+                        .get_sort(&term.ret.sort)
+                        .map_err(MismatchKind::Formula)?;
+                    let expected = self
+                        .env
+                        .parse_expr(&value.text, &vars, sort)
+                        .map_err(MismatchKind::Formula)?;
+                    let expected = self.translate(&expected);
+
+                    let (found, _) = self
+                        .verifier
+                        .get_definition_value(idx as u32)
+                        .map_err(|_| MismatchKind::Value)?
+                        .ok_or(MismatchKind::Definition)?;
+
+                    let (expected, _) = expected.renumber_dummies(term.binders.len() as u16);
+
+                    if expected != found {
+                        return Err(MismatchKind::Value);
+                    }
+                }
+            }
+            (Statement::Assert(assert), opcode::Statement::Axiom)
+            | (Statement::Assert(assert), opcode::Statement::Thm) => {
+                if assert.axiom != (kind == opcode::Statement::Axiom) {
+                    return Err(MismatchKind::Kind);
+                }
+
+                let found = table.get_theorem(idx as u32).ok_or(MismatchKind::Missing)?;
+                let binders = table
+                    .get_binders(found.get_binders())
+                    .ok_or(MismatchKind::Binders)?;
+
+                if binders != self.binders(&assert.binders)?.as_slice() {
+                    return Err(MismatchKind::Binders);
+                }
+
+                let (hyps, conclusion) = self
+                    .verifier
+                    .get_theorem_statement(idx as u32)
+                    .map_err(|_| MismatchKind::Conclusion)?;
+
+                let expected = assert
+                    .hyps
+                    .iter()
+                    .map(|x| self.env.parse_statement(&x.text, &assert.binders))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(MismatchKind::Formula)?;
+
+                if hyps.len() != expected.len()
+                    || hyps
+                        .iter()
+                        .zip(expected.iter())
+                        .any(|(x, y)| *x != self.translate(y))
+                {
+                    return Err(MismatchKind::Hypotheses);
+                }
+
+                let expected = self
+                    .env
+                    .parse_statement(&assert.conclusion.text, &assert.binders)
+                    .map_err(MismatchKind::Formula)?;
+
+                if conclusion != self.translate(&expected) {
+                    return Err(MismatchKind::Conclusion);
+                }
+            }
+            _ => return Err(MismatchKind::Kind),
+        }
+
+        Ok(())
+    }
+
+    fn binders(&self, binders: &[Binder]) -> Result<Vec<Var_>, MismatchKind> {
+        binders.iter().map(|x| self.binder(x, binders)).collect()
+    }
+
+    /// Encodes a binder the same way the file does, where the dependencies
+    /// are a bit mask over the bound variables in order.
+    fn binder(&self, binder: &Binder, binders: &[Binder]) -> Result<Var_, MismatchKind> {
+        let sort = self
+            .env
+            .get_sort(&binder.sort)
+            .map_err(MismatchKind::Formula)?;
+        let sort = *self.sorts.get(sort).ok_or(MismatchKind::Binders)?;
+
+        let bit = |name: &str| {
+            binders
+                .iter()
+                .filter(|x| x.bound)
+                .position(|x| x.name.as_deref() == Some(name))
+                .map(|x| 1u64 << x)
+        };
+
+        let deps = if binder.bound {
+            binder
+                .name
+                .as_deref()
+                .and_then(bit)
+                .ok_or(MismatchKind::Binders)?
+        } else {
+            binder
+                .deps
+                .iter()
+                .try_fold(0, |deps, x| bit(x).map(|x| deps | x))
+                .ok_or(MismatchKind::Binders)?
+        };
+
+        Ok(Var_::new(sort, deps, binder.bound))
+    }
+
+    /// Replaces the term indices of the specification with those of the file.
+    fn translate(&self, expr: &Expr) -> Expr {
+        match expr {
+            Expr::Var(x) => Expr::Var(*x),
+            Expr::Term(id, args) => Expr::Term(
+                self.terms.get(*id as usize).copied().unwrap_or(u32::MAX),
+                args.iter().map(|x| self.translate(x)).collect(),
+            ),
+        }
+    }
+}
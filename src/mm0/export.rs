@@ -0,0 +1,169 @@
+use super::{Mm0, Printer};
+use crate::expr::Expr;
+use crate::kernel::{opcode, KResult, Table, Term, Theorem, Var, Var_};
+use crate::Verifier;
+use std::fmt::Write;
+
+/// Writes the exported statements of a file as a specification.
+///
+/// The file does not store the names of variables, so they are named `v0`,
+/// `v1` and so on by binder position. Expressions are written as plain
+/// applications, since the notations are not stored either.
+pub fn export(verifier: &Verifier) -> KResult<String> {
+    let spec = Mm0::default();
+    let printer = Printer::new(verifier, &spec).expect("an empty specification is valid");
+    let mut out = String::from("delimiter $ ( ) $;\n");
+
+    for i in verifier.get_public_statements() {
+        let name = |x: Option<&str>| match x {
+            Some(name) => name.to_string(),
+            None => match i.kind {
+                opcode::Statement::Sort => format!("s{}", i.index),
+                opcode::Statement::TermDef => format!("t{}", i.index),
+                _ => format!("thm{}", i.index),
+            },
+        };
+
+        match i.kind {
+            opcode::Statement::Sort => {
+                let sort = verifier
+                    .table
+                    .get_sort(i.index as u8)
+                    .ok_or(crate::kernel::error::Kind::InvalidSort)?;
+                let modifiers = ["pure ", "strict ", "provable ", "free "];
+
+                for (bit, modifier) in modifiers.iter().enumerate() {
+                    if sort.0 & (1 << bit) != 0 {
+                        out.push_str(modifier);
+                    }
+                }
+
+                let _ = writeln!(out, "sort {};", name(verifier.get_sort_name(i.index)));
+            }
+            opcode::Statement::TermDef => {
+                let term = verifier
+                    .table
+                    .get_term(i.index as u32)
+                    .ok_or(crate::kernel::error::Kind::InvalidTerm)?;
+                let binders = verifier
+                    .table
+                    .get_binders(term.get_binders())
+                    .ok_or(crate::kernel::error::Kind::InvalidBinderIndices)?;
+
+                let keyword = if term.is_definition() { "def" } else { "term" };
+                let _ = write!(out, "{} {}", keyword, name(verifier.get_term_name(i.index)));
+
+                write_binders(&mut out, verifier, binders);
+
+                let value = verifier.get_definition_value(i.index as u32)?;
+
+                if let Some((_, dummies)) = &value {
+                    for (j, sort) in dummies.iter().enumerate() {
+                        let _ = write!(
+                            out,
+                            " {{.v{}: {}}}",
+                            binders.len() + j,
+                            sort_name(verifier, *sort)
+                        );
+                    }
+                }
+
+                let _ = write!(
+                    out,
+                    ": {}",
+                    type_of(verifier, binders, term.get_return_type())
+                );
+
+                if let Some((value, dummies)) = &value {
+                    let vars = var_names(binders.len() + dummies.len());
+
+                    let _ = write!(out, " = $ {} $", printer.to_display(value, &vars));
+                }
+
+                out.push_str(";\n");
+            }
+            opcode::Statement::Axiom | opcode::Statement::Thm => {
+                let thm = verifier
+                    .table
+                    .get_theorem(i.index as u32)
+                    .ok_or(crate::kernel::error::Kind::InvalidTheorem)?;
+                let binders = verifier
+                    .table
+                    .get_binders(thm.get_binders())
+                    .ok_or(crate::kernel::error::Kind::InvalidBinderIndices)?;
+
+                let keyword = if i.kind == opcode::Statement::Axiom {
+                    "axiom"
+                } else {
+                    "theorem"
+                };
+
+                let _ = write!(
+                    out,
+                    "{} {}",
+                    keyword,
+                    name(verifier.get_theorem_name(i.index))
+                );
+
+                write_binders(&mut out, verifier, binders);
+
+                let (hyps, conclusion) = verifier.get_theorem_statement(i.index as u32)?;
+                let vars = var_names(binders.len());
+                let display = |x: &Expr| printer.to_display(x, &vars).to_string();
+
+                out.push(':');
+
+                for hyp in &hyps {
+                    let _ = write!(out, "\n    $ {} $ >", display(hyp));
+                }
+
+                let _ = writeln!(out, "\n    $ {} $;", display(&conclusion));
+            }
+            _ => {}
+        }
+    }
+
+    Ok(out)
+}
+
+fn var_names(nr: usize) -> Vec<String> {
+    (0..nr).map(|x| format!("v{}", x)).collect()
+}
+
+fn sort_name(verifier: &Verifier, sort: u8) -> String {
+    match verifier.get_sort_name(sort as usize) {
+        Some(name) => name.to_string(),
+        None => format!("s{}", sort),
+    }
+}
+
+/// Returns the sort of a variable, followed by the bound variables it depends
+/// on.
+fn type_of(verifier: &Verifier, binders: &[Var_], ty: &Var_) -> String {
+    let mut ret = sort_name(verifier, ty.get_sort_idx());
+
+    let bound = binders.iter().enumerate().filter(|(_, x)| x.is_bound());
+
+    for (bit, (idx, _)) in bound.enumerate() {
+        if ty.get_deps() & (1 << bit) != 0 {
+            let _ = write!(ret, " v{}", idx);
+        }
+    }
+
+    ret
+}
+
+fn write_binders(out: &mut String, verifier: &Verifier, binders: &[Var_]) {
+    for (i, ty) in binders.iter().enumerate() {
+        if ty.is_bound() {
+            let _ = write!(
+                out,
+                " {{v{}: {}}}",
+                i,
+                sort_name(verifier, ty.get_sort_idx())
+            );
+        } else {
+            let _ = write!(out, " (v{}: {})", i, type_of(verifier, binders, ty));
+        }
+    }
+}
@@ -0,0 +1,339 @@
+use super::{Binder, Literal, Mm0, Notation, NotationKind, Statement, PREC_MAX};
+use crate::expr::Expr;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum MathError {
+    UnknownSort(String),
+    UnknownTerm(String),
+    UnexpectedToken(String),
+    UnexpectedEnd,
+    TypeMismatch { expected: String, found: String },
+    NotProvable(String),
+}
+
+type MResult<T> = Result<T, MathError>;
+
+struct TermInfo {
+    binders: Vec<usize>,
+    ret: usize,
+}
+
+/// The sorts, terms and notations of a specification, used to parse its
+/// formulas.
+///
+/// Term indices in the parsed expressions count the `term` and `def`
+/// statements of the specification, in order.
+pub struct Env<'a> {
+    mm0: &'a Mm0,
+    sort_names: Vec<&'a str>,
+    sorts: HashMap<&'a str, usize>,
+    provable: Vec<bool>,
+    terms: HashMap<&'a str, usize>,
+    term_info: Vec<TermInfo>,
+    prefixes: HashMap<&'a str, (usize, &'a Notation)>,
+    infixes: HashMap<&'a str, (usize, &'a Notation)>,
+    notations: HashMap<usize, &'a Notation>,
+    coercions: HashMap<(usize, usize), usize>,
+}
+
+impl<'a> Env<'a> {
+    pub fn new(mm0: &'a Mm0) -> MResult<Env<'a>> {
+        let mut env = Env {
+            mm0,
+            sort_names: Vec::new(),
+            sorts: HashMap::new(),
+            provable: Vec::new(),
+            terms: HashMap::new(),
+            term_info: Vec::new(),
+            prefixes: HashMap::new(),
+            infixes: HashMap::new(),
+            notations: HashMap::new(),
+            coercions: HashMap::new(),
+        };
+
+        for i in &mm0.statements {
+            match i {
+                Statement::Sort(sort) => {
+                    env.sorts.insert(&sort.name, env.sort_names.len());
+                    env.sort_names.push(&sort.name);
+                    env.provable.push(sort.provable);
+                }
+                Statement::Term(term) => {
+                    let binders = term
+                        .binders
+                        .iter()
+                        .map(|x| env.get_sort(&x.sort))
+                        .collect::<MResult<_>>()?;
+                    let ret = env.get_sort(&term.ret.sort)?;
+
+                    env.terms.insert(&term.name, env.term_info.len());
+                    env.term_info.push(TermInfo { binders, ret });
+                }
+                Statement::Assert(_) => {}
+            }
+        }
+
+        for i in &mm0.notations {
+            let term = env.get_term(&i.term)?;
+
+            match i.kind {
+                NotationKind::Infix { .. } => env.infixes.insert(&i.token, (term, i)),
+                _ => env.prefixes.insert(&i.token, (term, i)),
+            };
+
+            env.notations.entry(term).or_insert(i);
+        }
+
+        for i in &mm0.coercions {
+            let term = env.get_term(&i.term)?;
+            let from = env.get_sort(&i.from)?;
+            let to = env.get_sort(&i.to)?;
+
+            env.coercions.insert((from, to), term);
+        }
+
+        Ok(env)
+    }
+
+    pub fn get_sort(&self, name: &str) -> MResult<usize> {
+        self.sorts
+            .get(name)
+            .copied()
+            .ok_or_else(|| MathError::UnknownSort(name.to_string()))
+    }
+
+    pub fn get_term(&self, name: &str) -> MResult<usize> {
+        self.terms
+            .get(name)
+            .copied()
+            .ok_or_else(|| MathError::UnknownTerm(name.to_string()))
+    }
+
+    /// Returns the notation that is used to print the term `idx`, if any.
+    pub fn get_notation(&self, idx: usize) -> Option<&'a Notation> {
+        self.notations.get(&idx).copied()
+    }
+
+    /// Returns the coercion term from one sort to another.
+    pub fn get_coercion(&self, from: usize, to: usize) -> Option<usize> {
+        self.coercions.get(&(from, to)).copied()
+    }
+
+    pub fn is_coercion(&self, idx: usize) -> bool {
+        self.coercions.values().any(|x| *x == idx)
+    }
+
+    pub fn get_mm0(&self) -> &'a Mm0 {
+        self.mm0
+    }
+
+    fn tokenize<'b>(&self, text: &'b str) -> Vec<&'b str> {
+        let mut tokens = Vec::new();
+        let mut start = None;
+
+        for (i, c) in text.char_indices() {
+            if c.is_whitespace() || self.mm0.delimiters.contains(&c) {
+                if let Some(s) = start.take() {
+                    tokens.push(&text[s..i]);
+                }
+
+                if !c.is_whitespace() {
+                    tokens.push(&text[i..(i + c.len_utf8())]);
+                }
+            } else if start.is_none() {
+                start = Some(i);
+            }
+        }
+
+        if let Some(s) = start {
+            tokens.push(&text[s..]);
+        }
+
+        tokens
+    }
+
+    /// Parses a formula of the given sort, where the variables are numbered
+    /// by their position in `vars`.
+    pub fn parse_expr(&self, text: &str, vars: &[Binder], sort: usize) -> MResult<Expr> {
+        let (expr, found) = self.parse(text, vars)?;
+
+        self.coerce(expr, found, sort)
+    }
+
+    /// Parses a formula that has to be of a provable sort, inserting a
+    /// coercion into a provable sort if needed.
+    pub fn parse_statement(&self, text: &str, vars: &[Binder]) -> MResult<Expr> {
+        let (expr, found) = self.parse(text, vars)?;
+
+        if self.provable.get(found).copied().unwrap_or(false) {
+            return Ok(expr);
+        }
+
+        let target = self
+            .coercions
+            .iter()
+            .find(|(&(from, to), _)| from == found && self.provable[to])
+            .map(|(_, &term)| term);
+
+        match target {
+            Some(term) => Ok(Expr::Term(term as u32, vec![expr])),
+            None => Err(MathError::NotProvable(self.sort_names[found].to_string())),
+        }
+    }
+
+    fn parse(&self, text: &str, vars: &[Binder]) -> MResult<(Expr, usize)> {
+        let vars = vars
+            .iter()
+            .map(|x| Ok((x.name.as_deref(), self.get_sort(&x.sort)?)))
+            .collect::<MResult<Vec<_>>>()?;
+
+        let mut parser = MathParser {
+            env: self,
+            tokens: self.tokenize(text),
+            idx: 0,
+            vars,
+        };
+
+        let ret = parser.expr(0)?;
+
+        match parser.tokens.get(parser.idx) {
+            Some(x) => Err(MathError::UnexpectedToken(x.to_string())),
+            None => Ok(ret),
+        }
+    }
+
+    fn coerce(&self, expr: Expr, from: usize, to: usize) -> MResult<Expr> {
+        if from == to {
+            return Ok(expr);
+        }
+
+        match self.coercions.get(&(from, to)) {
+            Some(&term) => Ok(Expr::Term(term as u32, vec![expr])),
+            None => Err(MathError::TypeMismatch {
+                expected: self.sort_names[to].to_string(),
+                found: self.sort_names[from].to_string(),
+            }),
+        }
+    }
+}
+
+struct MathParser<'a, 'b> {
+    env: &'b Env<'a>,
+    tokens: Vec<&'b str>,
+    idx: usize,
+    vars: Vec<(Option<&'b str>, usize)>,
+}
+
+impl<'a, 'b> MathParser<'a, 'b> {
+    fn peek(&self) -> Option<&'b str> {
+        self.tokens.get(self.idx).copied()
+    }
+
+    fn next(&mut self) -> MResult<&'b str> {
+        let token = self.peek().ok_or(MathError::UnexpectedEnd)?;
+        self.idx += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, token: &str) -> MResult<()> {
+        match self.next()? {
+            x if x == token => Ok(()),
+            x => Err(MathError::UnexpectedToken(x.to_string())),
+        }
+    }
+
+    fn apply(&self, term: usize, args: Vec<(Expr, usize)>) -> MResult<(Expr, usize)> {
+        let info = &self.env.term_info[term];
+
+        let args = args
+            .into_iter()
+            .zip(info.binders.iter())
+            .map(|((expr, found), &sort)| self.env.coerce(expr, found, sort))
+            .collect::<MResult<Vec<_>>>()?;
+
+        Ok((Expr::Term(term as u32, args), info.ret))
+    }
+
+    fn expr(&mut self, min: u32) -> MResult<(Expr, usize)> {
+        let mut lhs = self.primary()?;
+
+        while let Some(&(term, notation)) = self.peek().and_then(|x| self.env.infixes.get(x)) {
+            if notation.prec < min {
+                break;
+            }
+
+            self.idx += 1;
+
+            let rhs = match notation.kind {
+                NotationKind::Infix { right: true } => self.expr(notation.prec)?,
+                _ => self.expr(notation.prec + 1)?,
+            };
+
+            lhs = self.apply(term, vec![lhs, rhs])?;
+        }
+
+        Ok(lhs)
+    }
+
+    fn primary(&mut self) -> MResult<(Expr, usize)> {
+        let token = self.next()?;
+
+        if token == "(" {
+            let ret = self.expr(0)?;
+            self.expect(")")?;
+            return Ok(ret);
+        }
+
+        if let Some(&(term, notation)) = self.env.prefixes.get(token) {
+            let nr_args = self.env.term_info[term].binders.len();
+
+            let args = match &notation.kind {
+                NotationKind::General(literals) => {
+                    let mut args = vec![None; nr_args];
+
+                    for (i, lit) in literals.iter().enumerate().skip(1) {
+                        match lit {
+                            Literal::Const(c, _) => self.expect(c)?,
+                            Literal::Var(idx) => {
+                                let prec = match literals.get(i + 1) {
+                                    Some(Literal::Const(_, q)) => (q + 1).min(PREC_MAX),
+                                    _ => notation.prec,
+                                };
+
+                                if let Some(arg) = args.get_mut(*idx) {
+                                    *arg = Some(self.expr(prec)?);
+                                }
+                            }
+                        }
+                    }
+
+                    args.into_iter()
+                        .map(|x| x.ok_or_else(|| MathError::UnexpectedToken(token.to_string())))
+                        .collect::<MResult<Vec<_>>>()?
+                }
+                _ => (0..nr_args)
+                    .map(|_| self.expr(notation.prec))
+                    .collect::<MResult<Vec<_>>>()?,
+            };
+
+            return self.apply(term, args);
+        }
+
+        if let Some(idx) = self.vars.iter().rposition(|x| x.0 == Some(token)) {
+            return Ok((Expr::Var(idx as u16), self.vars[idx].1));
+        }
+
+        if let Some(&term) = self.env.terms.get(token) {
+            let nr_args = self.env.term_info[term].binders.len();
+
+            let args = (0..nr_args)
+                .map(|_| self.expr(PREC_MAX))
+                .collect::<MResult<Vec<_>>>()?;
+
+            return self.apply(term, args);
+        }
+
+        Err(MathError::UnexpectedToken(token.to_string()))
+    }
+}
@@ -0,0 +1,166 @@
+//! A parser for `.mm0` specification files.
+//!
+//! The statements are parsed into a simple syntax tree that keeps formulas
+//! as text. Formulas are parsed on demand with `Env`, which resolves the
+//! notations declared in the file.
+
+pub mod check;
+mod export;
+pub mod math;
+mod parser;
+pub mod print;
+
+pub use check::{check, Mismatch, MismatchKind};
+pub use export::export;
+pub use math::{Env, MathError};
+pub use parser::{ParseError, ParseErrorKind};
+pub use print::Printer;
+
+/// The precedence of atoms, written as `max` in a specification.
+pub const PREC_MAX: u32 = 2048;
+
+/// The precedence of a prefix term application `foo x y`.
+pub const PREC_APP: u32 = 1024;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Sort {
+    pub name: String,
+    pub offset: usize,
+    pub pure: bool,
+    pub strict: bool,
+    pub provable: bool,
+    pub free: bool,
+}
+
+impl Sort {
+    /// Returns the modifiers in the encoding of `Sort_`.
+    pub fn modifiers(&self) -> u8 {
+        (self.pure as u8)
+            | (self.strict as u8) << 1
+            | (self.provable as u8) << 2
+            | (self.free as u8) << 3
+    }
+}
+
+/// A variable binder, or an anonymous one if it comes from an arrow type.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Binder {
+    pub name: Option<String>,
+    pub sort: String,
+    pub bound: bool,
+    pub deps: Vec<String>,
+}
+
+/// A math string, with the offset of its first character in the file.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Formula {
+    pub text: String,
+    pub offset: usize,
+}
+
+/// A `term` or a `def`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Term {
+    pub name: String,
+    pub offset: usize,
+    pub binders: Vec<Binder>,
+    pub ret: Binder,
+    /// `None` for terms, `Some(None)` for definitions without a value.
+    pub def: Option<Option<Formula>>,
+    pub dummies: Vec<Binder>,
+}
+
+/// An `axiom` or a `theorem`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Assert {
+    pub name: String,
+    pub offset: usize,
+    pub axiom: bool,
+    pub binders: Vec<Binder>,
+    pub hyps: Vec<Formula>,
+    pub conclusion: Formula,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Statement {
+    Sort(Sort),
+    Term(Term),
+    Assert(Assert),
+}
+
+impl Statement {
+    pub fn name(&self) -> &str {
+        match self {
+            Statement::Sort(x) => &x.name,
+            Statement::Term(x) => &x.name,
+            Statement::Assert(x) => &x.name,
+        }
+    }
+
+    pub fn offset(&self) -> usize {
+        match self {
+            Statement::Sort(x) => x.offset,
+            Statement::Term(x) => x.offset,
+            Statement::Assert(x) => x.offset,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Literal {
+    Const(String, u32),
+    /// A variable, with the index of the binder of the notation.
+    Var(usize),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum NotationKind {
+    Prefix,
+    Infix {
+        right: bool,
+    },
+    /// A general notation; the first literal is always a constant.
+    General(Vec<Literal>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Notation {
+    pub term: String,
+    pub token: String,
+    pub prec: u32,
+    pub kind: NotationKind,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Coercion {
+    pub term: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// A parsed specification file.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct Mm0 {
+    pub statements: Vec<Statement>,
+    pub notations: Vec<Notation>,
+    pub coercions: Vec<Coercion>,
+    pub delimiters: Vec<char>,
+}
+
+impl Mm0 {
+    pub fn parse(input: &str) -> Result<Mm0, ParseError> {
+        parser::parse(input)
+    }
+
+    pub fn get_statement(&self, name: &str) -> Option<&Statement> {
+        self.statements.iter().find(|x| x.name() == name)
+    }
+}
+
+/// Converts a byte offset in `input` into a zero based line and column.
+pub fn position(input: &str, offset: usize) -> (usize, usize) {
+    let prefix = &input[..offset.min(input.len())];
+    let start = prefix.rfind('\n').map(|x| x + 1).unwrap_or(0);
+
+    (prefix.matches('\n').count(), prefix.len() - start)
+}
@@ -0,0 +1,563 @@
+use super::{
+    Assert, Binder, Coercion, Formula, Literal, Mm0, Notation, NotationKind, Sort, Statement, Term,
+    PREC_MAX,
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+enum Token<'a> {
+    Ident(&'a str),
+    Number(u32),
+    Math(&'a str),
+    Punct(char),
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum ParseErrorKind {
+    UnexpectedEnd,
+    UnexpectedToken,
+    UnexpectedCharacter,
+    UnterminatedMath,
+    InvalidNumber,
+    InvalidDelimiter,
+    UnknownNotationVariable,
+    MissingConclusion,
+}
+
+/// An error in a specification file, at the given byte offset.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ParseError {
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+}
+
+type PResult<T> = Result<T, ParseError>;
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+fn tokenize(input: &str) -> PResult<Vec<(usize, Token<'_>)>> {
+    let mut tokens = Vec::new();
+    let bytes = input.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if input[i..].starts_with("--") {
+            i = input[i..].find('\n').map(|x| i + x).unwrap_or(bytes.len());
+        } else if c == '$' {
+            let end = input[(i + 1)..]
+                .find('$')
+                .map(|x| i + 1 + x)
+                .ok_or(ParseError {
+                    offset: i,
+                    kind: ParseErrorKind::UnterminatedMath,
+                })?;
+
+            tokens.push((i + 1, Token::Math(&input[(i + 1)..end])));
+            i = end + 1;
+        } else if is_ident_start(c) {
+            let len = input[i..]
+                .find(|x: char| !is_ident(x))
+                .unwrap_or(bytes.len() - i);
+
+            tokens.push((i, Token::Ident(&input[i..(i + len)])));
+            i += len;
+        } else if c.is_ascii_digit() {
+            let len = input[i..]
+                .find(|x: char| !x.is_ascii_digit())
+                .unwrap_or(bytes.len() - i);
+
+            let number = input[i..(i + len)].parse().map_err(|_| ParseError {
+                offset: i,
+                kind: ParseErrorKind::InvalidNumber,
+            })?;
+
+            tokens.push((i, Token::Number(number)));
+            i += len;
+        } else if "(){}:;>=.".contains(c) {
+            tokens.push((i, Token::Punct(c)));
+            i += 1;
+        } else {
+            return Err(ParseError {
+                offset: i,
+                kind: ParseErrorKind::UnexpectedCharacter,
+            });
+        }
+    }
+
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    tokens: Vec<(usize, Token<'a>)>,
+    idx: usize,
+    end: usize,
+    mm0: Mm0,
+}
+
+impl<'a> Parser<'a> {
+    fn offset(&self) -> usize {
+        self.tokens.get(self.idx).map(|x| x.0).unwrap_or(self.end)
+    }
+
+    fn error<T>(&self, kind: ParseErrorKind) -> PResult<T> {
+        let kind = if self.idx >= self.tokens.len() {
+            ParseErrorKind::UnexpectedEnd
+        } else {
+            kind
+        };
+
+        Err(ParseError {
+            offset: self.offset(),
+            kind,
+        })
+    }
+
+    fn peek(&self) -> Option<Token<'a>> {
+        self.tokens.get(self.idx).map(|x| x.1)
+    }
+
+    fn next(&mut self) -> PResult<Token<'a>> {
+        match self.peek() {
+            Some(token) => {
+                self.idx += 1;
+                Ok(token)
+            }
+            None => self.error(ParseErrorKind::UnexpectedEnd),
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(Token::Punct(c)) {
+            self.idx += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> PResult<()> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(ParseErrorKind::UnexpectedToken)
+        }
+    }
+
+    fn ident(&mut self) -> PResult<&'a str> {
+        match self.peek() {
+            Some(Token::Ident(x)) => {
+                self.idx += 1;
+                Ok(x)
+            }
+            _ => self.error(ParseErrorKind::UnexpectedToken),
+        }
+    }
+
+    fn math(&mut self) -> PResult<Formula> {
+        let offset = self.offset();
+
+        match self.peek() {
+            Some(Token::Math(x)) => {
+                self.idx += 1;
+                Ok(Formula {
+                    text: x.to_string(),
+                    offset,
+                })
+            }
+            _ => self.error(ParseErrorKind::UnexpectedToken),
+        }
+    }
+
+    fn prec(&mut self) -> PResult<u32> {
+        match self.next()? {
+            Token::Number(x) if x < PREC_MAX => Ok(x),
+            Token::Ident("max") => Ok(PREC_MAX),
+            _ => {
+                self.idx -= 1;
+                self.error(ParseErrorKind::InvalidNumber)
+            }
+        }
+    }
+
+    /// Parses a type, that is a sort followed by the variables it depends on.
+    fn ty(&mut self) -> PResult<Binder> {
+        let sort = self.ident()?.to_string();
+        let mut deps = Vec::new();
+
+        while let Some(Token::Ident(x)) = self.peek() {
+            deps.push(x.to_string());
+            self.idx += 1;
+        }
+
+        Ok(Binder {
+            name: None,
+            sort,
+            bound: false,
+            deps,
+        })
+    }
+
+    /// Parses a group of binders, returning the variables, the dummy
+    /// variables and the hypotheses.
+    fn binder_group(
+        &mut self,
+        binders: &mut Vec<Binder>,
+        dummies: &mut Vec<Binder>,
+        hyps: &mut Vec<Formula>,
+    ) -> PResult<()> {
+        let curly = match self.next()? {
+            Token::Punct('(') => false,
+            Token::Punct('{') => true,
+            _ => {
+                self.idx -= 1;
+                return self.error(ParseErrorKind::UnexpectedToken);
+            }
+        };
+
+        let mut names = Vec::new();
+
+        loop {
+            let dummy = self.eat('.');
+
+            match self.peek() {
+                Some(Token::Ident(x)) => {
+                    self.idx += 1;
+                    names.push((dummy, x));
+                }
+                _ if dummy => return self.error(ParseErrorKind::UnexpectedToken),
+                _ => break,
+            }
+        }
+
+        self.expect(':')?;
+
+        if let Some(Token::Math(_)) = self.peek() {
+            let formula = self.math()?;
+
+            for _ in names {
+                hyps.push(formula.clone());
+            }
+        } else {
+            let ty = self.ty()?;
+
+            for (dummy, name) in names {
+                let binder = Binder {
+                    name: Some(name.to_string()),
+                    bound: curly || dummy,
+                    ..ty.clone()
+                };
+
+                if dummy {
+                    dummies.push(binder);
+                } else {
+                    binders.push(binder);
+                }
+            }
+        }
+
+        self.expect(if curly { '}' } else { ')' })
+    }
+
+    fn binders(&mut self) -> PResult<(Vec<Binder>, Vec<Binder>, Vec<Formula>)> {
+        let mut binders = Vec::new();
+        let mut dummies = Vec::new();
+        let mut hyps = Vec::new();
+
+        while let Some(Token::Punct('(')) | Some(Token::Punct('{')) = self.peek() {
+            self.binder_group(&mut binders, &mut dummies, &mut hyps)?;
+        }
+
+        Ok((binders, dummies, hyps))
+    }
+
+    fn sort(&mut self, offset: usize) -> PResult<()> {
+        let mut sort = Sort {
+            name: String::new(),
+            offset,
+            pure: false,
+            strict: false,
+            provable: false,
+            free: false,
+        };
+
+        loop {
+            match self.ident()? {
+                "pure" => sort.pure = true,
+                "strict" => sort.strict = true,
+                "provable" => sort.provable = true,
+                "free" => sort.free = true,
+                "sort" => break,
+                _ => {
+                    self.idx -= 1;
+                    return self.error(ParseErrorKind::UnexpectedToken);
+                }
+            }
+        }
+
+        sort.name = self.ident()?.to_string();
+        self.expect(';')?;
+
+        self.mm0.statements.push(Statement::Sort(sort));
+
+        Ok(())
+    }
+
+    fn term(&mut self, offset: usize, def: bool) -> PResult<()> {
+        let name = self.ident()?.to_string();
+        let (mut binders, dummies, hyps) = self.binders()?;
+
+        if !hyps.is_empty() {
+            return self.error(ParseErrorKind::UnexpectedToken);
+        }
+
+        self.expect(':')?;
+
+        let mut ret = self.ty()?;
+
+        while self.eat('>') {
+            binders.push(ret);
+            ret = self.ty()?;
+        }
+
+        let def = if !def {
+            None
+        } else if self.eat('=') {
+            Some(Some(self.math()?))
+        } else {
+            Some(None)
+        };
+
+        self.expect(';')?;
+
+        self.mm0.statements.push(Statement::Term(Term {
+            name,
+            offset,
+            binders,
+            ret,
+            def,
+            dummies,
+        }));
+
+        Ok(())
+    }
+
+    fn assert(&mut self, offset: usize, axiom: bool) -> PResult<()> {
+        let name = self.ident()?.to_string();
+        let (mut binders, _, mut hyps) = self.binders()?;
+
+        self.expect(':')?;
+
+        let mut conclusion = None;
+
+        loop {
+            if let Some(Token::Math(_)) = self.peek() {
+                conclusion = Some(self.math()?);
+            } else {
+                binders.push(self.ty()?);
+            }
+
+            if !self.eat('>') {
+                break;
+            }
+
+            if let Some(formula) = conclusion.take() {
+                hyps.push(formula);
+            }
+        }
+
+        let conclusion = match conclusion {
+            Some(x) => x,
+            None => return self.error(ParseErrorKind::MissingConclusion),
+        };
+
+        self.expect(';')?;
+
+        self.mm0.statements.push(Statement::Assert(Assert {
+            name,
+            offset,
+            axiom,
+            binders,
+            hyps,
+            conclusion,
+        }));
+
+        Ok(())
+    }
+
+    fn delimiter(&mut self) -> PResult<()> {
+        let mut formula = self.math()?;
+
+        if let Some(Token::Math(_)) = self.peek() {
+            let right = self.math()?;
+            formula.text.push(' ');
+            formula.text.push_str(&right.text);
+        }
+
+        for token in formula.text.split_whitespace() {
+            let mut chars = token.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => self.mm0.delimiters.push(c),
+                _ => {
+                    return Err(ParseError {
+                        offset: formula.offset,
+                        kind: ParseErrorKind::InvalidDelimiter,
+                    })
+                }
+            }
+        }
+
+        self.expect(';')
+    }
+
+    fn constant(&mut self) -> PResult<String> {
+        let formula = self.math()?;
+
+        Ok(formula.text.trim().to_string())
+    }
+
+    fn simple_notation(&mut self, kind: NotationKind) -> PResult<()> {
+        let term = self.ident()?.to_string();
+        self.expect(':')?;
+        let token = self.constant()?;
+
+        if self.ident()? != "prec" {
+            self.idx -= 1;
+            return self.error(ParseErrorKind::UnexpectedToken);
+        }
+
+        let prec = self.prec()?;
+        self.expect(';')?;
+
+        self.mm0.notations.push(Notation {
+            term,
+            token,
+            prec,
+            kind,
+        });
+
+        Ok(())
+    }
+
+    fn prec_constant(&mut self) -> PResult<(String, u32)> {
+        self.expect('(')?;
+        let token = self.constant()?;
+        self.expect(':')?;
+        let prec = self.prec()?;
+        self.expect(')')?;
+
+        Ok((token, prec))
+    }
+
+    fn notation(&mut self) -> PResult<()> {
+        let term = self.ident()?.to_string();
+        let (binders, _, _) = self.binders()?;
+
+        self.expect(':')?;
+        self.ty()?;
+        self.expect('=')?;
+
+        let (token, prec) = self.prec_constant()?;
+        let mut literals = vec![Literal::Const(token.clone(), prec)];
+
+        loop {
+            match self.peek() {
+                Some(Token::Punct('(')) => {
+                    let (token, prec) = self.prec_constant()?;
+                    literals.push(Literal::Const(token, prec));
+                }
+                Some(Token::Ident(x)) => {
+                    let idx = binders
+                        .iter()
+                        .position(|b| b.name.as_deref() == Some(x))
+                        .map(Ok)
+                        .unwrap_or_else(|| self.error(ParseErrorKind::UnknownNotationVariable))?;
+
+                    self.idx += 1;
+                    literals.push(Literal::Var(idx));
+                }
+                _ => break,
+            }
+        }
+
+        self.expect(';')?;
+
+        self.mm0.notations.push(Notation {
+            term,
+            token,
+            prec,
+            kind: NotationKind::General(literals),
+        });
+
+        Ok(())
+    }
+
+    fn coercion(&mut self) -> PResult<()> {
+        let term = self.ident()?.to_string();
+        self.expect(':')?;
+        let from = self.ident()?.to_string();
+        self.expect('>')?;
+        let to = self.ident()?.to_string();
+        self.expect(';')?;
+
+        self.mm0.coercions.push(Coercion { term, from, to });
+
+        Ok(())
+    }
+
+    fn skip_statement(&mut self) -> PResult<()> {
+        while self.next()? != Token::Punct(';') {}
+
+        Ok(())
+    }
+
+    fn statement(&mut self) -> PResult<()> {
+        let offset = self.offset();
+
+        match self.ident()? {
+            "pure" | "strict" | "provable" | "free" | "sort" => {
+                self.idx -= 1;
+                self.sort(offset)
+            }
+            "term" => self.term(offset, false),
+            "def" => self.term(offset, true),
+            "axiom" => self.assert(offset, true),
+            "theorem" => self.assert(offset, false),
+            "delimiter" => self.delimiter(),
+            "prefix" => self.simple_notation(NotationKind::Prefix),
+            "infixl" => self.simple_notation(NotationKind::Infix { right: false }),
+            "infixr" => self.simple_notation(NotationKind::Infix { right: true }),
+            "notation" => self.notation(),
+            "coercion" => self.coercion(),
+            "input" | "output" => self.skip_statement(),
+            _ => {
+                self.idx -= 1;
+                self.error(ParseErrorKind::UnexpectedToken)
+            }
+        }
+    }
+}
+
+pub fn parse(input: &str) -> PResult<Mm0> {
+    let mut parser = Parser {
+        tokens: tokenize(input)?,
+        idx: 0,
+        end: input.len(),
+        mm0: Mm0::default(),
+    };
+
+    while parser.peek().is_some() {
+        parser.statement()?;
+    }
+
+    Ok(parser.mm0)
+}
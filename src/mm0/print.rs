@@ -0,0 +1,225 @@
+use super::{Env, Literal, MathError, Mm0, Notation, NotationKind, Statement, PREC_APP, PREC_MAX};
+use crate::expr::Expr;
+use crate::kernel::context::PackedPtr;
+use crate::kernel::{Context, KResult, Store, Store_, Table};
+use crate::Verifier;
+use core::fmt::{self, Display, Formatter};
+use std::collections::HashMap;
+
+/// Prints expressions of a file in the notation of its specification.
+///
+/// Terms are matched with the specification by name. Terms without a
+/// notation are printed as applications, and coercions are left out.
+pub struct Printer<'a> {
+    env: Env<'a>,
+    terms: HashMap<u32, usize>,
+    names: Vec<String>,
+}
+
+impl<'a> Printer<'a> {
+    pub fn new(verifier: &Verifier, mm0: &'a Mm0) -> Result<Printer<'a>, MathError> {
+        let env = Env::new(mm0)?;
+        let mut terms = HashMap::new();
+        let mut names = Vec::new();
+
+        while verifier.table.get_term(names.len() as u32).is_some() {
+            let id = names.len();
+            let name = match verifier.get_term_name(id) {
+                Some(name) => name.to_string(),
+                None => format!("t{}", id),
+            };
+
+            if let Ok(idx) = env.get_term(&name) {
+                terms.insert(id as u32, idx);
+            }
+
+            names.push(name);
+        }
+
+        Ok(Printer { env, terms, names })
+    }
+
+    pub fn to_display<'b>(&'b self, expr: &'b Expr, vars: &'b [String]) -> DisplayNotation<'b> {
+        DisplayNotation {
+            printer: self,
+            expr,
+            vars,
+        }
+    }
+
+    /// Prints an expression of the store of a `Context`.
+    pub fn display_ptr<S: Store>(
+        &self,
+        store: &S,
+        ptr: PackedPtr,
+        vars: &[String],
+    ) -> KResult<String> {
+        let expr = Expr::from_store(store, ptr)?;
+
+        Ok(self.to_display(&expr, vars).to_string())
+    }
+
+    /// Returns the names of the variables of a theorem, taken from the
+    /// specification if it declares the theorem.
+    pub fn theorem_vars(&self, verifier: &Verifier, id: u32) -> Vec<String> {
+        let binders = verifier
+            .get_theorem_name(id as usize)
+            .and_then(|name| self.env.get_mm0().get_statement(name));
+
+        match binders {
+            Some(Statement::Assert(assert)) => assert
+                .binders
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x.name.clone().unwrap_or_else(|| format!("v{}", i)))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Prints the hypotheses and the conclusion of a theorem, as built by
+    /// `Verifier::create_theorem_application`.
+    pub fn display_theorem(&self, verifier: &Verifier, id: u32) -> KResult<(Vec<String>, String)> {
+        let vars = self.theorem_vars(verifier, id);
+        let mut context = Context::<Store_>::default();
+
+        let (_, hyps, conclusion) = verifier.create_theorem_application(id, &mut context)?;
+        let hyps = hyps.to_vec();
+        let store = context.get_store();
+
+        let hyps = hyps
+            .iter()
+            .map(|x| self.display_ptr(store, *x, &vars))
+            .collect::<KResult<Vec<_>>>()?;
+        let conclusion = self.display_ptr(store, conclusion, &vars)?;
+
+        Ok((hyps, conclusion))
+    }
+}
+
+/// An expression together with the printer and the variable names used to
+/// print it.
+pub struct DisplayNotation<'a> {
+    printer: &'a Printer<'a>,
+    expr: &'a Expr,
+    vars: &'a [String],
+}
+
+impl<'a> DisplayNotation<'a> {
+    fn write(&self, f: &mut Formatter, expr: &Expr, prec: u32) -> fmt::Result {
+        let (id, args) = match expr {
+            Expr::Var(idx) => {
+                return match self.vars.get(*idx as usize) {
+                    Some(name) => write!(f, "{}", name),
+                    None => write!(f, "v{}", idx),
+                }
+            }
+            Expr::Term(id, args) => (*id, args),
+        };
+
+        let env = &self.printer.env;
+        let idx = self.printer.terms.get(&id).copied();
+
+        if let (Some(idx), [arg]) = (idx, args.as_slice()) {
+            if env.is_coercion(idx) {
+                return self.write(f, arg, prec);
+            }
+        }
+
+        match idx.and_then(|x| env.get_notation(x)) {
+            Some(notation) => self.notation(f, notation, args, prec),
+            None => self.application(f, id, args, prec),
+        }
+    }
+
+    fn application(&self, f: &mut Formatter, id: u32, args: &[Expr], prec: u32) -> fmt::Result {
+        let parens = !args.is_empty() && prec > PREC_APP;
+
+        if parens {
+            write!(f, "(")?;
+        }
+
+        match self.printer.names.get(id as usize) {
+            Some(name) => write!(f, "{}", name)?,
+            None => write!(f, "t{}", id)?,
+        }
+
+        for i in args {
+            write!(f, " ")?;
+            self.write(f, i, PREC_MAX)?;
+        }
+
+        if parens {
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+
+    fn notation(
+        &self,
+        f: &mut Formatter,
+        notation: &Notation,
+        args: &[Expr],
+        prec: u32,
+    ) -> fmt::Result {
+        let q = notation.prec;
+        let parens = q < prec;
+
+        if parens {
+            write!(f, "(")?;
+        }
+
+        match (&notation.kind, args) {
+            (NotationKind::Infix { right }, [lhs, rhs]) => {
+                let (l, r) = if *right { (q + 1, q) } else { (q, q + 1) };
+
+                self.write(f, lhs, l)?;
+                write!(f, " {} ", notation.token)?;
+                self.write(f, rhs, r)?;
+            }
+            (NotationKind::General(literals), _) => {
+                for (i, lit) in literals.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, " ")?;
+                    }
+
+                    match lit {
+                        Literal::Const(c, _) => write!(f, "{}", c)?,
+                        Literal::Var(idx) => {
+                            let prec = match literals.get(i + 1) {
+                                Some(Literal::Const(_, q)) => (q + 1).min(PREC_MAX),
+                                _ => q,
+                            };
+
+                            match args.get(*idx) {
+                                Some(arg) => self.write(f, arg, prec)?,
+                                None => write!(f, "?")?,
+                            }
+                        }
+                    }
+                }
+            }
+            (_, args) => {
+                write!(f, "{}", notation.token)?;
+
+                for i in args {
+                    write!(f, " ")?;
+                    self.write(f, i, q)?;
+                }
+            }
+        }
+
+        if parens {
+            write!(f, ")")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Display for DisplayNotation<'a> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.write(f, self.expr, 0)
+    }
+}
@@ -1,7 +1,8 @@
 use crate::kernel::opcode;
 use crate::kernel::Table_;
-use crate::statement_iter::{Statement, StatementIter, StatementOwned};
-use mmb_parser::{ProofStream, UnifyStream, Visitor};
+use crate::statement_iter::{Statement, StatementIter, StatementOwned, StatementSlice};
+use alloc::vec::Vec;
+use mmb_parser::{Mmb, ProofStream, UnifyStream, Visitor};
 
 pub struct UnifyCommands {
     data: Vec<opcode::Command<opcode::Unify>>,
@@ -18,23 +19,119 @@ impl UnifyStream for UnifyCommands {
     }
 }
 
+enum ProofStorage {
+    Contiguous,
+    Chunked(Vec<Vec<opcode::Command<opcode::Proof>>>),
+    Skipped(usize),
+}
+
 pub struct ProofCommands {
     data: Vec<opcode::Command<opcode::Proof>>,
     start_offset: usize,
+    storage: ProofStorage,
+}
+
+impl ProofCommands {
+    fn start(&mut self) {
+        match &mut self.storage {
+            ProofStorage::Contiguous => self.start_offset = self.data.len(),
+            ProofStorage::Chunked(chunks) => {
+                if !self.data.is_empty() {
+                    let mut chunk = core::mem::take(&mut self.data);
+                    chunk.shrink_to_fit();
+                    chunks.push(chunk);
+                }
+            }
+            ProofStorage::Skipped(len) => *len = 0,
+        }
+    }
+
+    /// Returns the proof stream of every statement that has one as a
+    /// separate vector, in statement order.
+    fn into_chunks(mut self, statements: &[Statement]) -> Vec<Vec<opcode::Command<opcode::Proof>>> {
+        self.start();
+
+        match self.storage {
+            ProofStorage::Chunked(chunks) => chunks,
+            _ => statements
+                .iter()
+                .filter_map(|x| x.proof)
+                .filter_map(|(start, end)| self.data.get(start..end))
+                .map(|x| x.to_vec())
+                .collect(),
+        }
+    }
 }
 
 impl ProofStream for ProofCommands {
     fn push(&mut self, value: opcode::Command<opcode::Proof>) {
-        self.data.push(value);
+        match &mut self.storage {
+            ProofStorage::Skipped(len) => *len += 1,
+            _ => self.data.push(value),
+        }
     }
 
     fn done(&self) -> (usize, usize) {
-        (self.start_offset, self.data.len())
+        match self.storage {
+            ProofStorage::Skipped(len) => (0, len),
+            _ => (self.start_offset, self.data.len()),
+        }
     }
 }
 
 use crate::kernel::{Sort_, Term_, Theorem_, Var_};
 
+/// The number of elements a `MmbVisitor` reserves memory for.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct Capacity {
+    pub sorts: usize,
+    pub terms: usize,
+    pub theorems: usize,
+    pub binders: usize,
+    pub unify: usize,
+    pub proofs: usize,
+}
+
+fn read_u32(file: &[u8], offset: usize) -> usize {
+    file.get(offset..(offset + 4))
+        .map(|x| u32::from_le_bytes([x[0], x[1], x[2], x[3]]) as usize)
+        .unwrap_or(0)
+}
+
+impl Capacity {
+    /// Estimates the capacities from the header of a file.
+    ///
+    /// Sorts, terms and theorems are counted from their tables, which the
+    /// parser has checked to lie within the file. The declarations
+    /// between the theorem table and the proof section hold the binders and
+    /// the unify streams, and every command takes at least one byte, which
+    /// bounds the rest. Commands are assumed to take two bytes on average.
+    pub fn from_mmb(mmb: &Mmb) -> Capacity {
+        let file = mmb.file;
+
+        let theorems_ptr = read_u32(file, 20);
+        let proofs_ptr = read_u32(file, 24);
+        let index_ptr = read_u32(file, 32);
+
+        let declarations = proofs_ptr.saturating_sub(theorems_ptr + mmb.theorems.len());
+
+        let proofs_end = if index_ptr > proofs_ptr {
+            index_ptr
+        } else {
+            file.len()
+        };
+
+        Capacity {
+            sorts: mmb.sorts.len(),
+            terms: mmb.terms.len() / 8,
+            theorems: mmb.theorems.len() / 8,
+            binders: declarations / 8,
+            unify: declarations / 2,
+            proofs: proofs_end.saturating_sub(proofs_ptr) / 2,
+        }
+    }
+}
+
 pub struct MmbVisitor<'a> {
     binders: Vec<Var_>,
     slices: Vec<&'a [u8]>,
@@ -53,22 +150,39 @@ pub struct MmbVisitor<'a> {
 }
 
 impl<'a> MmbVisitor<'a> {
+    /// Creates a visitor that does not reserve any memory up front.
     pub fn new() -> MmbVisitor<'a> {
+        Self::with_capacity(Capacity::default())
+    }
+
+    pub fn with_capacity(capacity: Capacity) -> MmbVisitor<'a> {
+        Self::create(capacity, ProofStorage::Contiguous)
+    }
+
+    fn create(capacity: Capacity, storage: ProofStorage) -> MmbVisitor<'a> {
+        let statements = capacity.sorts + capacity.terms + capacity.theorems;
+
+        let proofs = match storage {
+            ProofStorage::Contiguous => capacity.proofs,
+            _ => 0,
+        };
+
         MmbVisitor {
-            binders: Vec::with_capacity(1024 * 1024),
-            slices: Vec::with_capacity(1024 * 1024),
-            statements: Vec::with_capacity(1024 * 1024),
+            binders: Vec::with_capacity(capacity.binders),
+            slices: Vec::with_capacity(statements + capacity.terms + capacity.theorems),
+            statements: Vec::with_capacity(statements),
             uni_streams: UnifyCommands {
-                data: Vec::with_capacity(1024 * 1024),
+                data: Vec::with_capacity(capacity.unify),
                 start_offset: 0,
             },
             proof_stream: ProofCommands {
-                data: Vec::with_capacity(1024 * 1024),
+                data: Vec::with_capacity(proofs),
                 start_offset: 0,
+                storage,
             },
-            sorts: Vec::new(),
-            terms: Vec::new(),
-            theorems: Vec::new(),
+            sorts: Vec::with_capacity(capacity.sorts),
+            terms: Vec::with_capacity(capacity.terms),
+            theorems: Vec::with_capacity(capacity.theorems),
             sort_indices: Vec::new(),
             axiom_indices: Vec::new(),
             term_indices: Vec::new(),
@@ -76,7 +190,38 @@ impl<'a> MmbVisitor<'a> {
         }
     }
 
+    /// Creates a visitor that stores the proof stream of every statement in
+    /// its own allocation, so that `into_table` does not have to copy them.
+    ///
+    /// The proof capacity is ignored, as every proof stream is sized on its
+    /// own.
+    pub fn new_chunked(capacity: Capacity) -> MmbVisitor<'a> {
+        Self::create(capacity, ProofStorage::Chunked(Vec::new()))
+    }
+
+    /// Creates a visitor that only checks that the proof streams can be
+    /// parsed, without storing them.
+    ///
+    /// The table has to be paired with a stream that decodes the proofs from
+    /// the file itself, see `into_table_borrowed`. The proof capacity is
+    /// ignored.
+    pub fn new_skipping_proofs(capacity: Capacity) -> MmbVisitor<'a> {
+        Self::create(capacity, ProofStorage::Skipped(0))
+    }
+
+    /// Returns the bytes of every statement, followed by the bytes of the
+    /// unify stream of every term and then every theorem.
+    ///
+    /// Terms that are not definitions have an empty unify stream.
+    pub fn get_slices(&self) -> &[&'a [u8]] {
+        &self.slices
+    }
+
+    /// Returns the table and a stream that releases the proof commands of
+    /// every statement once it has been verified.
     pub fn into_table(self) -> (crate::kernel::Table_, StatementIter) {
+        let proofs = self.proof_stream.into_chunks(&self.statements);
+
         (
             Table_ {
                 sorts: self.sorts,
@@ -85,7 +230,28 @@ impl<'a> MmbVisitor<'a> {
                 unify: self.uni_streams.data,
                 binders: self.binders,
             },
-            StatementIter::new(self.statements, self.proof_stream.data),
+            StatementIter::new(self.statements, proofs),
+        )
+    }
+
+    /// Returns the table and a stream that decodes the statements and their
+    /// proofs directly from `proofs`, the proof section of the file.
+    ///
+    /// The table is decoded as in `into_table`, see `ZeroCopyVerifier` for
+    /// why it can not borrow from the file.
+    pub fn into_table_borrowed(
+        self,
+        proofs: &'a [u8],
+    ) -> (crate::kernel::Table_, StatementSlice<'a>) {
+        (
+            Table_ {
+                sorts: self.sorts,
+                theorems: self.theorems,
+                terms: self.terms,
+                unify: self.uni_streams.data,
+                binders: self.binders,
+            },
+            StatementSlice::new(proofs),
         )
     }
 
@@ -110,6 +276,12 @@ impl<'a> MmbVisitor<'a> {
     }
 }
 
+impl<'a> Default for MmbVisitor<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<'a> Visitor<'a> for MmbVisitor<'a> {
     type Binder = Var_;
     type Sort = Sort_;
@@ -157,7 +329,7 @@ impl<'a> Visitor<'a> for MmbVisitor<'a> {
     }
 
     fn start_proof_stream(&mut self) -> &mut ProofCommands {
-        self.proof_stream.start_offset = self.proof_stream.data.len();
+        self.proof_stream.start();
         &mut self.proof_stream
     }
 
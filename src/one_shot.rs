@@ -0,0 +1,112 @@
+use crate::kernel::stream::statement::Action;
+use crate::kernel::{Context, KResult, State, Stepper, Store_, Table_, Var_};
+use crate::mmb_visitor::{Capacity, MmbVisitor};
+use crate::statement_iter::{StatementIter, StatementSlice};
+use mmb_parser::Mmb;
+
+/// A verifier that checks every statement exactly once, in order.
+///
+/// Unlike `Verifier`, it can not seek, and it does not keep the name index
+/// around. The proof stream of a statement is freed as soon as the
+/// statement is verified, which keeps the memory usage low for big files.
+pub struct OneShotVerifier {
+    pub table: Table_,
+    pub context: Context<Store_>,
+    pub state: State,
+    stepper: Stepper<StatementIter, Var_>,
+}
+
+impl OneShotVerifier {
+    pub fn new(data: &[u8]) -> Option<OneShotVerifier> {
+        let data = Mmb::from(data)?;
+
+        let mut visitor = MmbVisitor::new_chunked(Capacity::from_mmb(&data));
+        data.visit(&mut visitor).ok()?;
+
+        let (table, stream) = visitor.into_table();
+
+        Some(OneShotVerifier {
+            table,
+            stepper: Stepper::new(stream),
+            context: Context::default(),
+            state: State::default(),
+        })
+    }
+
+    pub fn step<F: FnMut(Action, &Self)>(&mut self, f: &mut F) -> KResult<Option<()>> {
+        let x = self
+            .stepper
+            .step(&mut self.context, &mut self.state, &self.table)?;
+
+        if let Some(x) = x {
+            f(x, self);
+
+            Ok(Some(()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn run<F: FnMut(Action, &Self)>(&mut self, f: &mut F) -> KResult<()> {
+        while self.step(f)?.is_some() {}
+
+        Ok(())
+    }
+}
+
+/// A one-pass verifier that borrows the proofs of the file instead of
+/// copying them.
+///
+/// Statements and proof streams are decoded from `data` as they are
+/// verified, so apart from the table the memory usage does not depend on the
+/// size of the proofs. The input can be a memory-mapped file.
+///
+/// The table is still decoded into a `Table_`. The kernel's `Table` trait
+/// hands out `&[Command<Unify>]`, `&[Var_]` and references to decoded term
+/// and theorem entries, while the file stores variable-length commands and
+/// packed little-endian records, so a borrowed table needs a change to the
+/// kernel first.
+pub struct ZeroCopyVerifier<'a> {
+    pub table: Table_,
+    pub context: Context<Store_>,
+    pub state: State,
+    stepper: Stepper<StatementSlice<'a>, Var_>,
+}
+
+impl<'a> ZeroCopyVerifier<'a> {
+    pub fn new(data: &'a [u8]) -> Option<ZeroCopyVerifier<'a>> {
+        let data = Mmb::from(data)?;
+
+        let mut visitor = MmbVisitor::new_skipping_proofs(Capacity::from_mmb(&data));
+        data.visit(&mut visitor).ok()?;
+
+        let (table, stream) = visitor.into_table_borrowed(data.proofs);
+
+        Some(ZeroCopyVerifier {
+            table,
+            stepper: Stepper::new(stream),
+            context: Context::default(),
+            state: State::default(),
+        })
+    }
+
+    pub fn step<F: FnMut(Action, &Self)>(&mut self, f: &mut F) -> KResult<Option<()>> {
+        let x = self
+            .stepper
+            .step(&mut self.context, &mut self.state, &self.table)?;
+
+        if let Some(x) = x {
+            f(x, self);
+
+            Ok(Some(()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    pub fn run<F: FnMut(Action, &Self)>(&mut self, f: &mut F) -> KResult<()> {
+        while self.step(f)?.is_some() {}
+
+        Ok(())
+    }
+}
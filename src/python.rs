@@ -0,0 +1,235 @@
+//! Bindings for Python, built with `--features python`.
+//!
+//! The shared library is the module `trivial_verifier`, with a `Verifier`
+//! class. Expressions are nested tuples: a variable is its binder position,
+//! and a term application is a tuple of the term name and the arguments.
+
+use crate::expr::Expr;
+use crate::kernel::{error::Kind, opcode, Table, Term, Theorem, Var, Var_};
+use crate::Verifier;
+use pyo3::exceptions::{PyOSError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+fn error(e: Kind) -> PyErr {
+    PyValueError::new_err(format!("{:?}", e))
+}
+
+#[pyclass(name = "Verifier", unsendable)]
+pub struct PyVerifier {
+    inner: Verifier,
+}
+
+impl PyVerifier {
+    fn expr<'py>(&self, py: Python<'py>, expr: &Expr) -> PyResult<Bound<'py, PyAny>> {
+        match expr {
+            Expr::Var(idx) => Ok(idx.into_pyobject(py)?.into_any()),
+            Expr::Term(id, args) => {
+                let name = match self.inner.get_term_name(*id as usize) {
+                    Some(name) => name.to_string(),
+                    None => format!("t{}", id),
+                };
+
+                let mut items = vec![name.into_pyobject(py)?.into_any()];
+
+                for i in args {
+                    items.push(self.expr(py, i)?);
+                }
+
+                Ok(PyTuple::new(py, items)?.into_any())
+            }
+        }
+    }
+
+    fn binder(&self, var: &Var_) -> (Option<&str>, bool, u64) {
+        let sort = self.inner.get_sort_name(var.get_sort_idx() as usize);
+
+        (sort, var.is_bound(), var.get_deps())
+    }
+
+    fn binders(&self, binders: &[Var_]) -> Vec<(Option<&str>, bool, u64)> {
+        binders.iter().map(|x| self.binder(x)).collect()
+    }
+
+    fn locals(&self) -> (Vec<bool>, Vec<opcode::Statement>) {
+        let mut terms = Vec::new();
+        let mut theorems = Vec::new();
+
+        for i in self.inner.get_statements() {
+            if i.is_term() {
+                terms.push(i.is_local());
+            } else if i.is_theorem() {
+                theorems.push(i.kind);
+            }
+        }
+
+        (terms, theorems)
+    }
+}
+
+#[pymethods]
+impl PyVerifier {
+    /// Parses a file from its bytes.
+    #[new]
+    fn new(data: &[u8]) -> PyResult<PyVerifier> {
+        let inner =
+            Verifier::new(data).ok_or_else(|| PyValueError::new_err("not a valid mmb file"))?;
+
+        Ok(PyVerifier { inner })
+    }
+
+    /// Reads and parses a file.
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<PyVerifier> {
+        let data = std::fs::read(path).map_err(|e| PyOSError::new_err(e.to_string()))?;
+
+        PyVerifier::new(&data)
+    }
+
+    /// Verifies the remaining statements, raises `ValueError` with the error
+    /// kind of the kernel if one fails.
+    fn run(&mut self) -> PyResult<()> {
+        self.inner.run(&mut |_, _| {}).map_err(error)
+    }
+
+    /// Takes one step, and returns whether there was anything left to do.
+    ///
+    /// The callback is called with a description of the step.
+    #[pyo3(signature = (callback = None))]
+    fn step(&mut self, callback: Option<Bound<'_, PyAny>>) -> PyResult<bool> {
+        let mut result = Ok(());
+
+        let stepped = self
+            .inner
+            .step(&mut |action, _| {
+                if let (Some(f), Ok(())) = (&callback, &result) {
+                    result = f.call1((format!("{:?}", action),)).map(|_| ());
+                }
+            })
+            .map_err(error)?;
+
+        result?;
+
+        Ok(stepped.is_some())
+    }
+
+    fn sort_name(&self, idx: usize) -> Option<&str> {
+        self.inner.get_sort_name(idx)
+    }
+
+    fn term_name(&self, idx: usize) -> Option<&str> {
+        self.inner.get_term_name(idx)
+    }
+
+    fn theorem_name(&self, idx: usize) -> Option<&str> {
+        self.inner.get_theorem_name(idx)
+    }
+
+    fn sort_index(&self, name: &str) -> Option<usize> {
+        self.inner.get_sort_index(name)
+    }
+
+    fn term_index(&self, name: &str) -> Option<usize> {
+        self.inner.get_term_index(name)
+    }
+
+    fn theorem_index(&self, name: &str) -> Option<usize> {
+        self.inner.get_theorem_index(name)
+    }
+
+    /// Returns every term as a dict with its name, whether it is local or a
+    /// definition, its binders as `(sort, bound, deps)`, its return type and
+    /// the value of a definition.
+    fn terms<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let (locals, _) = self.locals();
+        let table = &self.inner.table;
+        let mut terms = Vec::new();
+
+        while let Some(term) = table.get_term(terms.len() as u32) {
+            let idx = terms.len();
+            let binders = table
+                .get_binders(term.get_binders())
+                .ok_or_else(|| error(Kind::InvalidBinderIndices))?;
+
+            let value = match self.inner.get_definition_value(idx as u32).map_err(error)? {
+                Some((value, _)) => Some(self.expr(py, &value)?),
+                None => None,
+            };
+
+            let dict = PyDict::new(py);
+            dict.set_item("index", idx)?;
+            dict.set_item("name", self.inner.get_term_name(idx))?;
+            dict.set_item("local", locals.get(idx).copied().unwrap_or(false))?;
+            dict.set_item("definition", term.is_definition())?;
+            dict.set_item("binders", self.binders(binders))?;
+            dict.set_item("return", self.binder(term.get_return_type()))?;
+            dict.set_item("value", value)?;
+
+            terms.push(dict);
+        }
+
+        Ok(terms)
+    }
+
+    /// Returns every theorem as a dict with its name, whether it is local or
+    /// an axiom, its binders, hypotheses and conclusion.
+    fn theorems<'py>(&self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let (_, kinds) = self.locals();
+        let table = &self.inner.table;
+        let mut theorems = Vec::new();
+
+        while let Some(thm) = table.get_theorem(theorems.len() as u32) {
+            let idx = theorems.len();
+            let binders = table
+                .get_binders(thm.get_binders())
+                .ok_or_else(|| error(Kind::InvalidBinderIndices))?;
+
+            let (hyps, conclusion) = self
+                .inner
+                .get_theorem_statement(idx as u32)
+                .map_err(error)?;
+
+            let hyps = hyps
+                .iter()
+                .map(|x| self.expr(py, x))
+                .collect::<PyResult<Vec<_>>>()?;
+
+            let kind = kinds.get(idx).copied();
+
+            let dict = PyDict::new(py);
+            dict.set_item("index", idx)?;
+            dict.set_item("name", self.inner.get_theorem_name(idx))?;
+            dict.set_item("local", kind == Some(opcode::Statement::LocalTerm))?;
+            dict.set_item("axiom", kind == Some(opcode::Statement::Axiom))?;
+            dict.set_item("binders", self.binders(binders))?;
+            dict.set_item("hypotheses", hyps)?;
+            dict.set_item("conclusion", self.expr(py, &conclusion)?)?;
+
+            theorems.push(dict);
+        }
+
+        Ok(theorems)
+    }
+
+    /// Returns the hypotheses and conclusion of theorem `idx` as expression
+    /// trees.
+    fn theorem_statement<'py>(
+        &self,
+        py: Python<'py>,
+        idx: u32,
+    ) -> PyResult<(Vec<Bound<'py, PyAny>>, Bound<'py, PyAny>)> {
+        let (hyps, conclusion) = self.inner.get_theorem_statement(idx).map_err(error)?;
+
+        let hyps = hyps
+            .iter()
+            .map(|x| self.expr(py, x))
+            .collect::<PyResult<_>>()?;
+
+        Ok((hyps, self.expr(py, &conclusion)?))
+    }
+}
+
+#[pymodule]
+fn trivial_verifier(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyVerifier>()
+}
@@ -0,0 +1,370 @@
+//! An interactive shell to explore a file, built with `--features repl`.
+//!
+//! Commands are read with history and with tab-completion of the commands
+//! and of the names of sorts, terms and theorems. `Repl::execute` runs a
+//! single command without the line editor.
+
+use crate::expr::{display_entry, Expr};
+use crate::kernel::context::PackedPtr;
+use crate::kernel::opcode;
+use crate::search::{self, Pattern, Query};
+use crate::trace;
+use crate::Verifier;
+use rustyline::completion::Completer;
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::path::Path;
+
+pub const COMMANDS: &[&str] = &[
+    "seek", "step", "stmt", "stack", "heap", "show", "proof", "deps", "axioms", "search", "help",
+    "quit",
+];
+
+const HELP: &str = "commands:
+    seek <theorem>      start verifying a theorem, by name or index
+    step                take one step in the current statement
+    stmt                show the statement that is being verified
+    stack               show the proof, hypothesis and unification stacks
+    heap                show the proof and unification heaps
+    show <theorem>      show the hypotheses and conclusion of a theorem
+    proof <theorem>     list the steps of a proof, which seeks past it
+    deps <theorem>      list the terms and theorems a proof refers to
+    axioms <theorem>    list the axioms a proof depends on
+    search <sexpr>      list the theorems matching a pattern
+    help                show this message
+    quit                leave the shell";
+
+pub struct Repl {
+    verifier: Verifier,
+}
+
+impl Repl {
+    pub fn new(verifier: Verifier) -> Repl {
+        Repl { verifier }
+    }
+
+    /// The names of every sort, term and theorem.
+    pub fn names(&self) -> Vec<String> {
+        let verifier = &self.verifier;
+        let table = &verifier.table;
+
+        let sorts = (0..table.sorts.len()).filter_map(|x| verifier.get_sort_name(x));
+        let terms = (0..table.terms.len()).filter_map(|x| verifier.get_term_name(x));
+        let theorems = (0..table.theorems.len()).filter_map(|x| verifier.get_theorem_name(x));
+
+        let mut names: Vec<_> = sorts
+            .chain(terms)
+            .chain(theorems)
+            .map(String::from)
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    fn term_name(&self, idx: usize) -> String {
+        match self.verifier.get_term_name(idx) {
+            Some(name) => name.to_string(),
+            None => format!("t{}", idx),
+        }
+    }
+
+    fn theorem_name(&self, idx: usize) -> String {
+        match self.verifier.get_theorem_name(idx) {
+            Some(name) => name.to_string(),
+            None => format!("thm{}", idx),
+        }
+    }
+
+    fn expr(&self, expr: &Expr) -> String {
+        expr.to_display(|x| self.verifier.get_term_name(x as usize))
+            .to_string()
+    }
+
+    fn entries(&self, title: &str, entries: &[PackedPtr]) -> String {
+        let store = self.verifier.context.get_store();
+        let mut out = format!("{}:", title);
+
+        for (i, ptr) in entries.iter().enumerate() {
+            let entry = display_entry(store, *ptr, |x| self.verifier.get_term_name(x as usize));
+
+            out += &format!("\n  {}: {}", i, entry);
+        }
+
+        out
+    }
+
+    fn theorem(&self, arg: &str) -> Result<usize, String> {
+        let count = self.verifier.table.theorems.len();
+
+        match arg.parse::<usize>() {
+            Ok(idx) if idx < count => Ok(idx),
+            Ok(idx) => Err(format!("no theorem {}", idx)),
+            Err(_) => self
+                .verifier
+                .get_theorem_index(arg)
+                .ok_or_else(|| format!("unknown theorem: {}", arg)),
+        }
+    }
+
+    fn seek(&mut self, arg: &str) -> Result<String, String> {
+        let idx = self.theorem(arg)?;
+        self.verifier.seek_theorem(idx);
+
+        Ok(format!("at {}", self.theorem_name(idx)))
+    }
+
+    fn step(&mut self) -> Result<String, String> {
+        let mut action = None;
+
+        let more = self
+            .verifier
+            .step_statement(&mut |x, _| action = Some(format!("{:?}", x)))
+            .map_err(|e| format!("{:?}", e))?;
+
+        Ok(match (action, more) {
+            (Some(action), Some(())) => action,
+            (Some(action), None) => format!("{}\nstatement done", action),
+            (None, _) => "nothing left to verify".to_string(),
+        })
+    }
+
+    fn stmt(&self) -> Result<String, String> {
+        let verifier = &self.verifier;
+        let position = verifier.get_position();
+
+        let statement = match verifier.get_statements().get(position) {
+            Some(statement) => *statement,
+            None => return Ok("at the end of the file".to_string()),
+        };
+
+        let name = verifier.get_statement_name(&statement).unwrap_or("?");
+        let kind = match statement.kind {
+            opcode::Statement::Sort => "sort",
+            opcode::Statement::TermDef => "term",
+            opcode::Statement::LocalDef => "local def",
+            opcode::Statement::Axiom => "axiom",
+            opcode::Statement::Thm => "theorem",
+            opcode::Statement::LocalTerm => "local theorem",
+            opcode::Statement::End => "end",
+        };
+
+        let mut out = format!("statement {}: {} {}", position, kind, name);
+
+        if statement.is_theorem() {
+            out += &format!("\n{}", self.show(&statement.index.to_string())?);
+        } else if statement.is_term() {
+            let value = verifier
+                .get_definition_value(statement.index as u32)
+                .map_err(|e| format!("{:?}", e))?;
+
+            if let Some((value, _)) = value {
+                out += &format!("\n  = {}", self.expr(&value));
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn stack(&self) -> String {
+        let context = &self.verifier.context;
+
+        [
+            self.entries("proof", context.get_proof_stack().as_slice()),
+            self.entries("hyp", context.get_hyp_stack().as_slice()),
+            self.entries("unify", context.get_unify_stack().as_slice()),
+        ]
+        .join("\n")
+    }
+
+    fn heap(&self) -> String {
+        let context = &self.verifier.context;
+
+        [
+            self.entries("proof", context.get_proof_heap().as_slice()),
+            self.entries("unify", context.get_unify_heap().as_slice()),
+        ]
+        .join("\n")
+    }
+
+    fn show(&self, arg: &str) -> Result<String, String> {
+        let idx = self.theorem(arg)?;
+        let (hyps, conclusion) = self
+            .verifier
+            .get_theorem_statement(idx as u32)
+            .map_err(|e| format!("{:?}", e))?;
+
+        let mut out = String::new();
+
+        for i in &hyps {
+            out += &format!("  hypothesis {}\n", self.expr(i));
+        }
+
+        out += &format!("  conclusion {}", self.expr(&conclusion));
+
+        Ok(out)
+    }
+
+    fn proof(&mut self, arg: &str) -> Result<String, String> {
+        let idx = self.theorem(arg)?;
+        let trace = trace::trace(&mut self.verifier, idx).ok_or("no such theorem")?;
+
+        let listing = trace::listing(&self.verifier, &trace, |x| self.expr(x));
+
+        Ok(listing.trim_end().to_string())
+    }
+
+    fn deps(&self, arg: &str) -> Result<String, String> {
+        let (terms, theorems) = self.verifier.get_dependencies(self.theorem(arg)?);
+
+        let terms: Vec<_> = terms.iter().map(|x| self.term_name(*x)).collect();
+        let theorems: Vec<_> = theorems.iter().map(|x| self.theorem_name(*x)).collect();
+
+        Ok(format!(
+            "terms: {}\ntheorems: {}",
+            terms.join(" "),
+            theorems.join(" ")
+        ))
+    }
+
+    fn axioms(&self, arg: &str) -> Result<String, String> {
+        let axioms: Vec<_> = self
+            .verifier
+            .get_axioms(self.theorem(arg)?)
+            .iter()
+            .map(|x| self.theorem_name(*x))
+            .collect();
+
+        Ok(axioms.join(" "))
+    }
+
+    fn search(&self, arg: &str) -> Result<String, String> {
+        let pattern = Pattern::parse(arg, &self.verifier).map_err(|e| format!("{:?}", e))?;
+        let query = Query {
+            pattern: Some(pattern),
+            ..Query::default()
+        };
+
+        let hits = search::search(&self.verifier, &query).map_err(|e| format!("{:?}", e))?;
+        let lines: Vec<_> = hits
+            .iter()
+            .map(|x| self.theorem_name(x.theorem as usize))
+            .collect();
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Runs one command, and returns its output.
+    pub fn execute(&mut self, line: &str) -> Result<String, String> {
+        let line = line.trim();
+        let (command, arg) = match line.find(' ') {
+            Some(x) => (&line[..x], line[x..].trim()),
+            None => (line, ""),
+        };
+
+        match (command, arg) {
+            ("seek", arg) if !arg.is_empty() => self.seek(arg),
+            ("step", "") => self.step(),
+            ("stmt", "") => self.stmt(),
+            ("stack", "") => Ok(self.stack()),
+            ("heap", "") => Ok(self.heap()),
+            ("show", arg) if !arg.is_empty() => self.show(arg),
+            ("proof", arg) if !arg.is_empty() => self.proof(arg),
+            ("deps", arg) if !arg.is_empty() => self.deps(arg),
+            ("axioms", arg) if !arg.is_empty() => self.axioms(arg),
+            ("search", arg) if !arg.is_empty() => self.search(arg),
+            ("help", "") => Ok(HELP.to_string()),
+            _ => Err(format!("unknown command: {}, see help", line)),
+        }
+    }
+}
+
+/// Completes the first word with a command and the others with names.
+struct Names(Vec<String>);
+
+impl Completer for Names {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos].rfind([' ', '(']);
+        let word = &line[start.map_or(0, |x| x + 1)..pos];
+
+        let candidates = match start {
+            None => COMMANDS
+                .iter()
+                .filter(|x| x.starts_with(word))
+                .map(|x| x.to_string())
+                .collect(),
+            Some(_) => self
+                .0
+                .iter()
+                .filter(|x| x.starts_with(word))
+                .cloned()
+                .collect(),
+        };
+
+        Ok((pos - word.len(), candidates))
+    }
+}
+
+impl Hinter for Names {
+    type Hint = String;
+}
+
+impl Highlighter for Names {}
+
+impl Validator for Names {}
+
+impl Helper for Names {}
+
+/// Reads commands until `quit` or the end of the input, with the history
+/// kept in `history` if it is given.
+pub fn run(repl: &mut Repl, history: Option<&Path>) -> rustyline::Result<()> {
+    let mut editor = Editor::<Names, DefaultHistory>::new()?;
+    editor.set_helper(Some(Names(repl.names())));
+
+    if let Some(path) = history {
+        // there is no history yet on the first run
+        let _ = editor.load_history(path);
+    }
+
+    loop {
+        let line = match editor.readline("> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e),
+        };
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        editor.add_history_entry(line.as_str())?;
+
+        if line.trim() == "quit" {
+            break;
+        }
+
+        match repl.execute(&line) {
+            Ok(out) if out.is_empty() => {}
+            Ok(out) => println!("{}", out),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+
+    if let Some(path) = history {
+        editor.save_history(path)?;
+    }
+
+    Ok(())
+}
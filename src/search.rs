@@ -0,0 +1,243 @@
+use crate::expr::Expr;
+use crate::kernel::{KResult, Table, Term};
+use crate::Verifier;
+use regex::Regex;
+
+/// A pattern that is matched against expression trees.
+///
+/// Patterns are written as s-expressions over term names, where `_` matches
+/// any subexpression. A bare name stands for a term without arguments, so
+/// `(= _ (+ _ 0))` matches every expression of the form `a = b + 0`.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum Pattern {
+    Wildcard,
+    Term(u32, Vec<Pattern>),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub enum PatternError {
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UnknownTerm(String),
+    WrongArity {
+        name: String,
+        expected: usize,
+        found: usize,
+    },
+    TrailingInput,
+}
+
+impl Pattern {
+    pub fn parse(input: &str, verifier: &Verifier) -> Result<Pattern, PatternError> {
+        let tokens = tokenize(input);
+        let mut tokens = tokens.iter().copied().peekable();
+
+        let pattern = parse_pattern(&mut tokens, verifier)?;
+
+        if tokens.next().is_some() {
+            Err(PatternError::TrailingInput)
+        } else {
+            Ok(pattern)
+        }
+    }
+
+    pub fn matches(&self, expr: &Expr) -> bool {
+        match (self, expr) {
+            (Pattern::Wildcard, _) => true,
+            (Pattern::Term(id, pats), Expr::Term(t, args)) => {
+                id == t
+                    && pats.len() == args.len()
+                    && pats.iter().zip(args.iter()).all(|(p, e)| p.matches(e))
+            }
+            (Pattern::Term(..), Expr::Var(_)) => false,
+        }
+    }
+
+    /// Returns true if the pattern matches a proper subexpression of `expr`.
+    pub fn matches_below(&self, expr: &Expr) -> bool {
+        match expr {
+            Expr::Var(_) => false,
+            Expr::Term(_, args) => args
+                .iter()
+                .any(|x| self.matches(x) || self.matches_below(x)),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (i, c) in input.char_indices() {
+        if c == '(' || c == ')' || c.is_whitespace() {
+            if let Some(s) = start.take() {
+                tokens.push(&input[s..i]);
+            }
+
+            if !c.is_whitespace() {
+                tokens.push(&input[i..(i + 1)]);
+            }
+        } else if start.is_none() {
+            start = Some(i);
+        }
+    }
+
+    if let Some(s) = start {
+        tokens.push(&input[s..]);
+    }
+
+    tokens
+}
+
+fn parse_pattern<'a, I>(
+    tokens: &mut std::iter::Peekable<I>,
+    verifier: &Verifier,
+) -> Result<Pattern, PatternError>
+where
+    I: Iterator<Item = &'a str>,
+{
+    match tokens.next() {
+        None => Err(PatternError::UnexpectedEnd),
+        Some("(") => {
+            let name = match tokens.next() {
+                None => return Err(PatternError::UnexpectedEnd),
+                Some(x) if x == "(" || x == ")" || x == "_" => {
+                    return Err(PatternError::UnexpectedToken(x.to_string()))
+                }
+                Some(x) => x,
+            };
+
+            let mut args = Vec::new();
+
+            loop {
+                match tokens.peek() {
+                    None => return Err(PatternError::UnexpectedEnd),
+                    Some(&")") => {
+                        tokens.next();
+                        break;
+                    }
+                    Some(_) => args.push(parse_pattern(tokens, verifier)?),
+                }
+            }
+
+            term_pattern(name, args, verifier)
+        }
+        Some(")") => Err(PatternError::UnexpectedToken(")".to_string())),
+        Some("_") => Ok(Pattern::Wildcard),
+        Some(name) => term_pattern(name, Vec::new(), verifier),
+    }
+}
+
+fn term_pattern(
+    name: &str,
+    args: Vec<Pattern>,
+    verifier: &Verifier,
+) -> Result<Pattern, PatternError> {
+    let id = verifier
+        .get_term_index(name)
+        .ok_or_else(|| PatternError::UnknownTerm(name.to_string()))?;
+
+    let expected = verifier
+        .table
+        .get_term(id as u32)
+        .map(|x| x.get_binders().len())
+        .ok_or_else(|| PatternError::UnknownTerm(name.to_string()))?;
+
+    if expected != args.len() {
+        return Err(PatternError::WrongArity {
+            name: name.to_string(),
+            expected,
+            found: args.len(),
+        });
+    }
+
+    Ok(Pattern::Term(id as u32, args))
+}
+
+/// Where in a theorem statement a pattern matched.
+///
+/// The order of the variants is the order in which hits are ranked.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Location {
+    Conclusion,
+    Hypothesis,
+    Subterm,
+}
+
+/// A theorem search. Every filter that is set has to match for a theorem to
+/// be part of the result.
+#[derive(Debug, Default)]
+pub struct Query {
+    pub pattern: Option<Pattern>,
+    pub mentions: Vec<u32>,
+    pub name: Option<Regex>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Hit {
+    pub theorem: u32,
+    pub location: Option<Location>,
+    pub size: usize,
+}
+
+impl Query {
+    fn locate(&self, hyps: &[Expr], conclusion: &Expr) -> Option<Option<Location>> {
+        let pattern = match &self.pattern {
+            Some(pattern) => pattern,
+            None => return Some(None),
+        };
+
+        if pattern.matches(conclusion) {
+            Some(Some(Location::Conclusion))
+        } else if hyps.iter().any(|x| pattern.matches(x)) {
+            Some(Some(Location::Hypothesis))
+        } else if pattern.matches_below(conclusion) || hyps.iter().any(|x| pattern.matches_below(x))
+        {
+            Some(Some(Location::Subterm))
+        } else {
+            None
+        }
+    }
+}
+
+/// Runs a query against every theorem of the verifier.
+///
+/// Hits are ranked by where the pattern matched, then by the size of the
+/// statement, so that the most general lemmas come first.
+pub fn search(verifier: &Verifier, query: &Query) -> KResult<Vec<Hit>> {
+    let mut hits = Vec::new();
+
+    for id in 0..verifier.table.nr_theorems() {
+        if let Some(regex) = &query.name {
+            match verifier.get_theorem_name(id as usize) {
+                Some(name) if regex.is_match(name) => {}
+                _ => continue,
+            }
+        }
+
+        let (hyps, conclusion) = verifier.get_theorem_statement(id)?;
+
+        let mentioned = query
+            .mentions
+            .iter()
+            .all(|&t| conclusion.mentions(t) || hyps.iter().any(|x| x.mentions(t)));
+
+        if !mentioned {
+            continue;
+        }
+
+        if let Some(location) = query.locate(&hyps, &conclusion) {
+            let size = conclusion.size() + hyps.iter().map(Expr::size).sum::<usize>();
+
+            hits.push(Hit {
+                theorem: id,
+                location,
+                size,
+            });
+        }
+    }
+
+    hits.sort_by_key(|x| (x.location, x.size, x.theorem));
+
+    Ok(hits)
+}
@@ -0,0 +1,496 @@
+//! A JSON-RPC 2.0 server that keeps one file loaded.
+//!
+//! Messages are framed with a `Content-Length` header, as in the language
+//! server protocol. The methods are:
+//!
+//! ```text
+//! load          { path }                       -> { sorts, terms, theorems }
+//! verify        {}                             -> { statements }
+//! seekTheorem   { name } or { index }          -> { index }
+//! step          {}                             -> { action }
+//! getStatement  { kind, name } or { kind, index } -> a statement as in `dump`
+//! stacks        {}                             -> { proof, hyp, unify }
+//! search        { pattern, mentions, name }    -> [{ theorem, name, location, size }]
+//! dependencies  { name } or { index }          -> { terms, theorems, axioms }
+//! shutdown      {}                             -> null
+//! ```
+//!
+//! `kind` is one of `sort`, `term` and `theorem`. While verifying, the server
+//! sends `progress` notifications with `{ done, total }`, and an `error`
+//! notification with `{ statement, name, kind }` if a statement fails.
+
+use crate::dump::{dump_sort, dump_term, dump_theorem};
+use crate::expr::Expr;
+use crate::kernel::context::{PackedPtr, Stack};
+use crate::kernel::{error::Kind, KResult};
+use crate::search::{self, Location, Pattern, Query};
+use crate::Verifier;
+use regex::Regex;
+use serde_json::{json, Value};
+use std::io::{self, BufRead, Read, Write};
+
+/// Statements between two progress notifications.
+const PROGRESS_INTERVAL: usize = 1000;
+
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// A method that needs a file was called before `load`.
+pub const NOT_LOADED: i64 = -32000;
+/// A statement failed to verify, or the kernel rejected a query.
+pub const KERNEL_ERROR: i64 = -32001;
+/// The file could not be read or parsed.
+pub const LOAD_ERROR: i64 = -32002;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: &str) -> RpcError {
+        RpcError {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    pub fn to_json(&self) -> Value {
+        match &self.data {
+            Some(data) => json!({ "code": self.code, "message": self.message, "data": data }),
+            None => json!({ "code": self.code, "message": self.message }),
+        }
+    }
+}
+
+impl From<Kind> for RpcError {
+    fn from(kind: Kind) -> RpcError {
+        RpcError::new(KERNEL_ERROR, &format!("{:?}", kind))
+    }
+}
+
+type RpcResult = Result<Value, RpcError>;
+
+fn invalid_params(message: &str) -> RpcError {
+    RpcError::new(INVALID_PARAMS, message)
+}
+
+fn stack_to_json(verifier: &Verifier, stack: &Stack) -> KResult<Value> {
+    let store = verifier.context.get_store();
+    let names = |x: u32| verifier.get_term_name(x as usize);
+
+    let entries = stack
+        .as_slice()
+        .iter()
+        .map(|ptr: &PackedPtr| {
+            let kind = if ptr.as_expr().is_some() {
+                "expr"
+            } else if ptr.as_proof().is_some() {
+                "proof"
+            } else {
+                return Ok(json!({ "kind": "conv", "expr": ptr.to_display(store).to_string() }));
+            };
+
+            let expr = Expr::from_store(store, *ptr)?;
+
+            Ok(json!({ "kind": kind, "expr": expr.to_display(names).to_string() }))
+        })
+        .collect::<KResult<Vec<_>>>()?;
+
+    Ok(Value::from(entries))
+}
+
+/// The state of the server, which can be driven without any framing through
+/// `handle`.
+#[derive(Default)]
+pub struct Server {
+    verifier: Option<Verifier>,
+    shutdown: bool,
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server::default()
+    }
+
+    /// Whether a `shutdown` request was handled.
+    pub fn is_shut_down(&self) -> bool {
+        self.shutdown
+    }
+
+    /// Handles one request or notification, and returns the response for a
+    /// request. Notifications of the server are passed to `notify`.
+    pub fn handle(&mut self, message: &Value, notify: &mut dyn FnMut(Value)) -> Option<Value> {
+        let id = message.get("id").cloned();
+
+        let result = match message.get("method").and_then(|x| x.as_str()) {
+            Some(method) => {
+                let params = message.get("params").cloned().unwrap_or(Value::Null);
+                self.call(method, &params, notify)
+            }
+            None => Err(RpcError::new(INVALID_REQUEST, "missing method")),
+        };
+
+        let id = id?;
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({ "jsonrpc": "2.0", "id": id, "error": e.to_json() }),
+        })
+    }
+
+    fn call(&mut self, method: &str, params: &Value, notify: &mut dyn FnMut(Value)) -> RpcResult {
+        match method {
+            "load" => self.load(params),
+            "verify" => self.verify(notify),
+            "seekTheorem" => self.seek_theorem(params),
+            "step" => self.step(),
+            "getStatement" => self.get_statement(params),
+            "stacks" => self.stacks(),
+            "search" => self.search(params),
+            "dependencies" => self.dependencies(params),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            _ => Err(RpcError::new(METHOD_NOT_FOUND, method)),
+        }
+    }
+
+    fn verifier(&self) -> Result<&Verifier, RpcError> {
+        self.verifier
+            .as_ref()
+            .ok_or_else(|| RpcError::new(NOT_LOADED, "no file is loaded"))
+    }
+
+    fn verifier_mut(&mut self) -> Result<&mut Verifier, RpcError> {
+        self.verifier
+            .as_mut()
+            .ok_or_else(|| RpcError::new(NOT_LOADED, "no file is loaded"))
+    }
+
+    fn load(&mut self, params: &Value) -> RpcResult {
+        let path = params["path"]
+            .as_str()
+            .ok_or_else(|| invalid_params("expected a path"))?;
+
+        let data = std::fs::read(path)
+            .map_err(|e| RpcError::new(LOAD_ERROR, &format!("{}: {}", path, e)))?;
+
+        let verifier = Verifier::new(&data)
+            .ok_or_else(|| RpcError::new(LOAD_ERROR, &format!("{}: not a valid mmb file", path)))?;
+
+        let table = &verifier.table;
+        let result = json!({
+            "sorts": table.sorts.len(),
+            "terms": table.terms.len(),
+            "theorems": table.theorems.len(),
+        });
+
+        self.verifier = Some(verifier);
+
+        Ok(result)
+    }
+
+    fn verify(&mut self, notify: &mut dyn FnMut(Value)) -> RpcResult {
+        let verifier = self.verifier_mut()?;
+        let statements = verifier.get_statements();
+        let total = statements.len();
+
+        verifier.seek(0);
+
+        for (i, statement) in statements.iter().enumerate() {
+            if let Err(e) = verifier.run_statement(&mut |_, _| {}) {
+                let data = json!({
+                    "statement": i,
+                    "name": verifier.get_statement_name(statement),
+                    "kind": format!("{:?}", e),
+                });
+
+                notify(json!({ "jsonrpc": "2.0", "method": "error", "params": data }));
+
+                return Err(RpcError {
+                    data: Some(data),
+                    ..RpcError::from(e)
+                });
+            }
+
+            if (i + 1) % PROGRESS_INTERVAL == 0 || i + 1 == total {
+                notify(json!({
+                    "jsonrpc": "2.0",
+                    "method": "progress",
+                    "params": { "done": i + 1, "total": total },
+                }));
+            }
+        }
+
+        Ok(json!({ "statements": total }))
+    }
+
+    /// Looks up a term or theorem by `name`, or checks that `index` exists.
+    fn lookup(
+        &self,
+        params: &Value,
+        by_name: fn(&Verifier, &str) -> Option<usize>,
+        count: usize,
+    ) -> Result<usize, RpcError> {
+        let verifier = self.verifier()?;
+
+        if let Some(name) = params["name"].as_str() {
+            by_name(verifier, name)
+                .ok_or_else(|| invalid_params(&format!("unknown name: {}", name)))
+        } else if let Some(idx) = params["index"].as_u64() {
+            Some(idx as usize)
+                .filter(|x| *x < count)
+                .ok_or_else(|| invalid_params(&format!("index out of range: {}", idx)))
+        } else {
+            Err(invalid_params("expected a name or an index"))
+        }
+    }
+
+    fn theorem(&self, params: &Value) -> Result<usize, RpcError> {
+        let count = self.verifier()?.table.theorems.len();
+
+        self.lookup(params, Verifier::get_theorem_index, count)
+    }
+
+    fn seek_theorem(&mut self, params: &Value) -> RpcResult {
+        let idx = self.theorem(params)?;
+        self.verifier_mut()?.seek_theorem(idx);
+
+        Ok(json!({ "index": idx }))
+    }
+
+    fn step(&mut self) -> RpcResult {
+        let mut action = None;
+
+        self.verifier_mut()?
+            .step(&mut |x, _| action = Some(format!("{:?}", x)))?;
+
+        Ok(json!({ "action": action }))
+    }
+
+    fn get_statement(&self, params: &Value) -> RpcResult {
+        let verifier = self.verifier()?;
+        let table = &verifier.table;
+
+        match params["kind"].as_str() {
+            Some("sort") => {
+                let idx = self.lookup(params, Verifier::get_sort_index, table.sorts.len())?;
+
+                Ok(dump_sort(verifier, idx).ok_or(Kind::InvalidSort)?)
+            }
+            Some("term") => {
+                let idx = self.lookup(params, Verifier::get_term_index, table.terms.len())?;
+
+                Ok(dump_term(verifier, idx)?)
+            }
+            Some("theorem") => Ok(dump_theorem(verifier, self.theorem(params)?)?),
+            _ => Err(invalid_params("expected a kind of sort, term or theorem")),
+        }
+    }
+
+    fn stacks(&self) -> RpcResult {
+        let verifier = self.verifier()?;
+        let context = &verifier.context;
+
+        Ok(json!({
+            "proof": stack_to_json(verifier, context.get_proof_stack())?,
+            "hyp": stack_to_json(verifier, context.get_hyp_stack())?,
+            "unify": stack_to_json(verifier, context.get_unify_stack())?,
+        }))
+    }
+
+    fn search(&self, params: &Value) -> RpcResult {
+        let verifier = self.verifier()?;
+        let mut query = Query::default();
+
+        if let Some(pattern) = params["pattern"].as_str() {
+            let pattern = Pattern::parse(pattern, verifier)
+                .map_err(|e| invalid_params(&format!("invalid pattern: {:?}", e)))?;
+            query.pattern = Some(pattern);
+        }
+
+        for i in params["mentions"].as_array().into_iter().flatten() {
+            let name = i.as_str().unwrap_or_default();
+            let id = verifier
+                .get_term_index(name)
+                .ok_or_else(|| invalid_params(&format!("unknown term: {}", name)))?;
+            query.mentions.push(id as u32);
+        }
+
+        if let Some(name) = params["name"].as_str() {
+            let regex =
+                Regex::new(name).map_err(|e| invalid_params(&format!("invalid regex: {}", e)))?;
+            query.name = Some(regex);
+        }
+
+        let hits = search::search(verifier, &query)?
+            .iter()
+            .map(|hit| {
+                let location = hit.location.map(|x| match x {
+                    Location::Conclusion => "conclusion",
+                    Location::Hypothesis => "hypothesis",
+                    Location::Subterm => "subterm",
+                });
+
+                json!({
+                    "theorem": hit.theorem,
+                    "name": verifier.get_theorem_name(hit.theorem as usize),
+                    "location": location,
+                    "size": hit.size,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        Ok(Value::from(hits))
+    }
+
+    /// The terms and theorems the proof of a theorem refers to, and every
+    /// axiom it depends on through them.
+    fn dependencies(&self, params: &Value) -> RpcResult {
+        let verifier = self.verifier()?;
+        let theorem = self.theorem(params)?;
+
+        let (terms, theorems) = verifier.get_dependencies(theorem);
+
+        let term_names: Vec<_> = terms.iter().map(|x| verifier.get_term_name(*x)).collect();
+        let theorem_names: Vec<_> = theorems
+            .iter()
+            .map(|x| verifier.get_theorem_name(*x))
+            .collect();
+        let axiom_names: Vec<_> = verifier
+            .get_axioms(theorem)
+            .iter()
+            .map(|x| verifier.get_theorem_name(*x))
+            .collect();
+
+        Ok(json!({
+            "terms": term_names,
+            "theorems": theorem_names,
+            "axioms": axiom_names,
+        }))
+    }
+}
+
+/// Reads the content of one message, or `None` at the end of the input.
+pub fn read_message<R: BufRead>(input: &mut R) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            length = value.trim().parse().ok();
+        }
+    }
+
+    let length = length
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing Content-Length"))?;
+
+    // the length is not trusted, so the buffer only grows as content arrives
+    let mut content = Vec::new();
+    input.take(length as u64).read_to_end(&mut content)?;
+
+    if content.len() < length {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "message shorter than its Content-Length",
+        ));
+    }
+
+    Ok(Some(content))
+}
+
+pub fn write_message<W: Write>(output: &mut W, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+/// Something that answers framed JSON-RPC messages.
+pub trait Handler {
+    /// Handles one request or notification, and returns the response for a
+    /// request. Notifications of the handler are passed to `notify`.
+    fn handle(&mut self, message: &Value, notify: &mut dyn FnMut(Value)) -> Option<Value>;
+
+    /// Whether no more messages should be read.
+    fn is_done(&self) -> bool;
+}
+
+impl Handler for Server {
+    fn handle(&mut self, message: &Value, notify: &mut dyn FnMut(Value)) -> Option<Value> {
+        Server::handle(self, message, notify)
+    }
+
+    fn is_done(&self) -> bool {
+        self.is_shut_down()
+    }
+}
+
+/// Passes messages from `input` to the handler until the input ends or the
+/// handler is done.
+pub fn run<H: Handler, R: BufRead, W: Write>(
+    handler: &mut H,
+    mut input: R,
+    mut output: W,
+) -> io::Result<()> {
+    while let Some(content) = read_message(&mut input)? {
+        let response = match serde_json::from_slice::<Value>(&content) {
+            Ok(message) => {
+                let mut failed = Ok(());
+                let mut notify = |x: Value| {
+                    if failed.is_ok() {
+                        failed = write_message(&mut output, &x);
+                    }
+                };
+
+                let response = handler.handle(&message, &mut notify);
+                failed?;
+
+                response
+            }
+            Err(e) => Some(json!({
+                "jsonrpc": "2.0",
+                "id": null,
+                "error": RpcError::new(PARSE_ERROR, &e.to_string()).to_json(),
+            })),
+        };
+
+        if let Some(response) = response {
+            write_message(&mut output, &response)?;
+        }
+
+        if handler.is_done() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs a `Server` on `input` until it ends or the server is shut down.
+pub fn serve<R: BufRead, W: Write>(input: R, output: W) -> io::Result<()> {
+    run(&mut Server::new(), input, output)
+}
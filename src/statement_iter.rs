@@ -1,7 +1,13 @@
 use crate::kernel::opcode;
 use crate::kernel::stream::{self, statement::StatementStream};
 use crate::kernel::State;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
 
+/// A statement of the proof stream.
+///
+/// Note that `opcode::Statement::LocalTerm` is the local theorem statement
+/// (`0x0E`), there are no local terms.
 #[derive(Debug)]
 pub struct Statement {
     pub code: opcode::Statement,
@@ -10,8 +16,8 @@ pub struct Statement {
 
 #[derive(Debug)]
 pub struct StatementIter {
-    data: std::vec::IntoIter<Statement>,
-    proofs: Option<std::vec::IntoIter<opcode::Command<opcode::Proof>>>,
+    data: alloc::vec::IntoIter<Statement>,
+    proofs: alloc::vec::IntoIter<Vec<opcode::Command<opcode::Proof>>>,
     ps: Option<(usize, usize)>,
 }
 
@@ -25,15 +31,23 @@ pub struct StatementOwned {
     pub theorem_indices: Vec<usize>,
 
     idx: usize,
-    proofs: Option<Vec<opcode::Command<opcode::Proof>>>,
+    /// Shared with the proof stream that is being checked, so that it is not
+    /// lost if the kernel drops the stream on an error. The `Vec` is moved
+    /// into the `Arc` as is, so the proofs are never copied.
+    proofs: Arc<Vec<opcode::Command<opcode::Proof>>>,
     ps: Option<(usize, usize)>,
 }
 
 impl StatementIter {
-    pub fn new(data: Vec<Statement>, proofs: Vec<opcode::Command<opcode::Proof>>) -> StatementIter {
+    /// Creates a stream from the statements and the proof streams of those
+    /// statements that have one, in the same order.
+    pub fn new(
+        data: Vec<Statement>,
+        proofs: Vec<Vec<opcode::Command<opcode::Proof>>>,
+    ) -> StatementIter {
         StatementIter {
             data: data.into_iter(),
-            proofs: Some(proofs.into_iter()),
+            proofs: proofs.into_iter(),
             ps: None,
         }
     }
@@ -55,11 +69,32 @@ impl StatementOwned {
             term_indices,
             theorem_indices,
             idx: 0,
-            proofs: Some(proofs),
+            proofs: Arc::new(proofs),
             ps: None,
         }
     }
 
+    pub fn get_statements(&self) -> &[Statement] {
+        &self.data
+    }
+
+    /// Returns the index of the next statement to be read.
+    pub fn get_position(&self) -> usize {
+        self.idx
+    }
+
+    /// Returns the proof stream of the statement `idx`, without the end
+    /// command.
+    pub fn get_proof(&self, idx: usize) -> Option<&[opcode::Command<opcode::Proof>]> {
+        let (start, end) = self.data.get(idx)?.proof?;
+        let proof = self.proofs.get(start..end)?;
+
+        match proof.split_last() {
+            Some((last, rest)) if last.opcode == opcode::Proof::End => Some(rest),
+            _ => Some(proof),
+        }
+    }
+
     pub fn seek_to(&mut self, idx: usize) -> State {
         let mut state = State::default();
 
@@ -70,7 +105,7 @@ impl StatementOwned {
                 Statement::Sort => state.increment_current_sort(),
                 Statement::TermDef => state.increment_current_term(),
                 Statement::LocalDef => state.increment_current_term(),
-                Statement::LocalTerm => state.increment_current_term(),
+                Statement::LocalTerm => state.increment_current_theorem(),
                 Statement::Axiom => state.increment_current_theorem(),
                 Statement::Thm => state.increment_current_theorem(),
             }
@@ -103,7 +138,7 @@ impl Iterator for StatementIter {
                 }
                 Statement::LocalTerm => {
                     self.ps = statement.proof;
-                    Some(stream::statement::Opcode::TermDef)
+                    Some(stream::statement::Opcode::Thm)
                 }
                 Statement::Axiom => {
                     self.ps = statement.proof;
@@ -142,7 +177,7 @@ impl Iterator for StatementOwned {
                 }
                 Statement::LocalTerm => {
                     self.ps = statement.proof;
-                    Some(stream::statement::Opcode::TermDef)
+                    Some(stream::statement::Opcode::Thm)
                 }
                 Statement::Axiom => {
                     self.ps = statement.proof;
@@ -164,16 +199,21 @@ impl StatementStream for StatementIter {
 
     fn take_proof_stream(&mut self) -> Option<Self::ProofStream> {
         let len = self.ps.unwrap_or((0, 0));
-        let proofs = self.proofs.take()?;
+
+        let proofs = if self.ps.is_some() {
+            self.proofs.next()?
+        } else {
+            Vec::new()
+        };
 
         Some(ProofIter {
-            proofs,
+            proofs: proofs.into_iter(),
             max_len: (len.1 - len.0),
         })
     }
 
-    fn put_proof_stream(&mut self, proofs: Self::ProofStream) {
-        self.proofs = Some(proofs.proofs);
+    fn put_proof_stream(&mut self, _proofs: Self::ProofStream) {
+        // the stream is dropped here, which frees the proof of the statement
     }
 }
 
@@ -182,29 +222,27 @@ impl StatementStream for StatementOwned {
 
     fn take_proof_stream(&mut self) -> Option<Self::ProofStream> {
         let len = self.ps.unwrap_or((0, 0));
-        let proofs = self.proofs.take()?;
-
         Some(ProofOwned {
-            proofs,
+            proofs: self.proofs.clone(),
             idx: len.0,
             max_len: (len.1 - len.0),
         })
     }
 
-    fn put_proof_stream(&mut self, proofs: Self::ProofStream) {
-        self.proofs = Some(proofs.proofs);
+    fn put_proof_stream(&mut self, _proofs: Self::ProofStream) {
+        // the stream only holds another reference to `proofs`
     }
 }
 
 #[derive(Debug)]
 pub struct ProofIter {
-    proofs: std::vec::IntoIter<opcode::Command<opcode::Proof>>,
+    proofs: alloc::vec::IntoIter<opcode::Command<opcode::Proof>>,
     max_len: usize,
 }
 
 #[derive(Debug)]
 pub struct ProofOwned {
-    proofs: Vec<opcode::Command<opcode::Proof>>,
+    proofs: Arc<Vec<opcode::Command<opcode::Proof>>>,
     idx: usize,
     max_len: usize,
 }
@@ -237,3 +275,110 @@ impl Iterator for ProofOwned {
         }
     }
 }
+
+use core::convert::TryFrom;
+
+/// Decodes a single command, returning it with the remaining input.
+fn parse_command<T: TryFrom<u8>>(data: &[u8]) -> Option<(opcode::Command<T>, &[u8])> {
+    let (&code, rest) = data.split_first()?;
+
+    let size = match code & 0xC0 {
+        0x00 => 0,
+        0x40 => 1,
+        0x80 => 2,
+        _ => 4,
+    };
+
+    let operand = rest
+        .get(..size)?
+        .iter()
+        .rev()
+        .fold(0, |acc, &x| (acc << 8) | x as u32);
+
+    let opcode = T::try_from(code & 0x3F).ok()?;
+
+    Some((opcode::Command { opcode, operand }, rest.get(size..)?))
+}
+
+/// A statement stream that decodes statements and proofs on demand from the
+/// proof section of a file, without copying it.
+#[derive(Debug)]
+pub struct StatementSlice<'a> {
+    data: &'a [u8],
+    ps: Option<&'a [u8]>,
+}
+
+impl<'a> StatementSlice<'a> {
+    pub fn new(data: &'a [u8]) -> StatementSlice<'a> {
+        StatementSlice { data, ps: None }
+    }
+}
+
+impl<'a> Iterator for StatementSlice<'a> {
+    type Item = stream::statement::Opcode;
+
+    fn next(&mut self) -> Option<stream::statement::Opcode> {
+        self.ps = None;
+
+        let (command, rest) = parse_command::<opcode::Statement>(self.data)?;
+
+        use opcode::Statement;
+
+        if let Statement::End = command.opcode {
+            self.data = &[];
+            return Some(stream::statement::Opcode::End);
+        }
+
+        let header = self.data.len() - rest.len();
+        let statement = self.data.get(..(command.operand as usize))?;
+        let proof = statement.get(header..)?;
+
+        self.data = &self.data[statement.len()..];
+
+        if !proof.is_empty() {
+            self.ps = Some(proof);
+        }
+
+        match command.opcode {
+            Statement::End => Some(stream::statement::Opcode::End),
+            Statement::Sort => Some(stream::statement::Opcode::Sort),
+            Statement::TermDef => Some(stream::statement::Opcode::TermDef),
+            Statement::LocalDef => Some(stream::statement::Opcode::TermDef),
+            Statement::LocalTerm => Some(stream::statement::Opcode::Thm),
+            Statement::Axiom => Some(stream::statement::Opcode::Axiom),
+            Statement::Thm => Some(stream::statement::Opcode::Thm),
+        }
+    }
+}
+
+impl<'a> StatementStream for StatementSlice<'a> {
+    type ProofStream = ProofSlice<'a>;
+
+    fn take_proof_stream(&mut self) -> Option<Self::ProofStream> {
+        Some(ProofSlice {
+            data: self.ps.take().unwrap_or(&[]),
+        })
+    }
+
+    fn put_proof_stream(&mut self, _proofs: Self::ProofStream) {}
+}
+
+#[derive(Debug)]
+pub struct ProofSlice<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Iterator for ProofSlice<'a> {
+    type Item = opcode::Command<opcode::Proof>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (command, rest) = parse_command::<opcode::Proof>(self.data)?;
+
+        self.data = match command.opcode {
+            opcode::Proof::End => &[],
+            _ => rest,
+        };
+
+        Some(command)
+    }
+}
@@ -0,0 +1,330 @@
+//! A listing of the steps of a proof, in the style of `show proof` in
+//! Metamath.
+//!
+//! `trace` replays the proof stream of a theorem through the stepper of a
+//! `Verifier`, and records for every proof command the earlier steps whose
+//! results it consumed and the entry it left on top of the proof stack.
+//! Steps are numbered from 0 in the order of the proof stream.
+
+use crate::expr::Expr;
+use crate::kernel::context::PackedPtr;
+use crate::kernel::error::Kind;
+use crate::kernel::opcode::{Command, Proof, Unify};
+use crate::kernel::stream::proof::Action as ProofAction;
+use crate::kernel::stream::statement::{Action, AxiomThmAction};
+use crate::kernel::{Store, Table, Term, Theorem};
+use crate::Verifier;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// An entry of the proof stack, copied out of the store.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Entry {
+    Expr(Expr),
+    /// The proof of an expression.
+    Proof(Expr),
+    /// The proof that the first expression is convertible to the second.
+    Conv(Expr, Expr),
+    /// The obligation to show that the first expression is convertible to
+    /// the second.
+    CoConv(Expr, Expr),
+}
+
+impl Entry {
+    /// Copies the entry on top of a stack. Conversions take their second
+    /// expression from the entry below.
+    fn from_stack<S: Store>(store: &S, stack: &[PackedPtr]) -> Option<Entry> {
+        let (top, rest) = stack.split_last()?;
+        let expr = Expr::from_store(store, *top).ok()?;
+        let below = || rest.last().and_then(|x| Expr::from_store(store, *x).ok());
+
+        if top.as_proof().is_some() {
+            Some(Entry::Proof(expr))
+        } else if top.as_conv().is_some() {
+            Some(Entry::Conv(expr, below()?))
+        } else if top.as_co_conv().is_some() {
+            Some(Entry::CoConv(expr, below()?))
+        } else {
+            Some(Entry::Expr(expr))
+        }
+    }
+
+    /// Prints the entry with `expr` printing the expressions.
+    pub fn to_string_with<F: Fn(&Expr) -> String>(&self, expr: F) -> String {
+        match self {
+            Entry::Expr(e) => expr(e),
+            Entry::Proof(e) => format!("|- {}", expr(e)),
+            Entry::Conv(a, b) => format!("{} = {}", expr(a), expr(b)),
+            Entry::CoConv(a, b) => format!("{} =?= {}", expr(a), expr(b)),
+        }
+    }
+}
+
+/// A proof command, together with its effect.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Step {
+    /// The position of the command in the proof stream.
+    pub index: usize,
+    pub command: Command<Proof>,
+    /// The earlier steps whose results the command takes from the proof
+    /// stack or, for `Ref`, from the heap.
+    pub uses: Vec<usize>,
+    /// The heap slot the command added an entry to.
+    pub saved: Option<usize>,
+    /// The entry on top of the proof stack, if the command pushed one.
+    pub result: Option<Entry>,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Trace {
+    pub steps: Vec<Step>,
+    /// The error the proof failed with, after the last step.
+    pub error: Option<Kind>,
+}
+
+/// The number of entries a command that succeeded took from the proof stack.
+fn pops(verifier: &Verifier, command: Command<Proof>) -> usize {
+    let table = &verifier.table;
+    let idx = command.operand;
+
+    match command.opcode {
+        Proof::End | Proof::Ref | Proof::Dummy | Proof::Save => 0,
+        Proof::Term | Proof::TermSave => table.get_term(idx).map_or(0, |x| x.get_binders().len()),
+        // the target, the arguments and a proof for every hypothesis
+        Proof::Thm | Proof::ThmSave => table.get_theorem(idx).map_or(0, |x| {
+            let hyps = table
+                .get_unify_commands(x.get_unify_commands())
+                .unwrap_or_default()
+                .iter()
+                .filter(|x| x.opcode == Unify::Hyp)
+                .count();
+
+            1 + x.get_binders().len() + hyps
+        }),
+        Proof::Hyp => 1,
+        Proof::Conv
+        | Proof::Refl
+        | Proof::Symm
+        | Proof::Cong
+        | Proof::ConvCut
+        | Proof::ConvRef
+        | Proof::ConvSave => 2,
+        // the term and its unfolding, then the obligation below them
+        Proof::Unfold => 4,
+    }
+}
+
+/// Follows the proof stack and the heap, with the step that pushed each
+/// entry.
+#[derive(Default)]
+struct Tracer {
+    steps: Vec<Step>,
+    /// The step that pushed each entry of the proof stack.
+    stack_steps: Vec<usize>,
+    /// The step that added each heap slot, which is `None` for the binders.
+    heap_steps: Vec<Option<usize>>,
+    /// A theorem or unfold command whose application is not done yet.
+    pending: Option<(usize, Command<Proof>)>,
+}
+
+impl Tracer {
+    fn start(&mut self, verifier: &Verifier) {
+        let heap = verifier.context.get_proof_heap().as_slice();
+
+        self.heap_steps = vec![None; heap.len()];
+    }
+
+    fn command_done(&mut self, index: usize, command: Command<Proof>, verifier: &Verifier) {
+        let step = self.steps.len();
+        let context = &verifier.context;
+        let stack = context.get_proof_stack().as_slice();
+        let heap = context.get_proof_heap().as_slice();
+
+        let kept = self
+            .stack_steps
+            .len()
+            .saturating_sub(pops(verifier, command))
+            .min(stack.len());
+
+        let mut uses = self.stack_steps.split_off(kept);
+        uses.dedup();
+
+        if command.opcode == Proof::Ref {
+            uses.extend(
+                self.heap_steps
+                    .get(command.operand as usize)
+                    .copied()
+                    .flatten(),
+            );
+        }
+
+        self.stack_steps.resize(stack.len(), step);
+
+        let saved = if heap.len() > self.heap_steps.len() {
+            self.heap_steps.resize(heap.len(), Some(step));
+            Some(heap.len() - 1)
+        } else {
+            None
+        };
+
+        let result = if stack.len() > kept {
+            Entry::from_stack(context.get_store(), stack)
+        } else {
+            None
+        };
+
+        self.steps.push(Step {
+            index,
+            command,
+            uses,
+            saved,
+            result,
+        });
+    }
+
+    fn action(&mut self, action: Action, verifier: &Verifier) {
+        let action = match action {
+            Action::AxiomThm(AxiomThmAction::StartProof) => return self.start(verifier),
+            Action::AxiomThm(AxiomThmAction::Proof(x)) => x,
+            _ => return,
+        };
+
+        match action {
+            ProofAction::Cmd(_, command) if command.opcode == Proof::End => {}
+            ProofAction::Cmd(index, command) => match command.opcode {
+                Proof::Thm | Proof::ThmSave | Proof::Unfold => {
+                    self.pending = Some((index, command))
+                }
+                _ => self.command_done(index, command, verifier),
+            },
+            ProofAction::TheoremDone | ProofAction::UnfoldDone => {
+                if let Some((index, command)) = self.pending.take() {
+                    self.command_done(index, command, verifier);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Replays the proof of theorem `idx`. This moves the verifier past the
+/// theorem, or into it if the proof fails.
+pub fn trace(verifier: &mut Verifier, idx: usize) -> Option<Trace> {
+    if !verifier.seek_theorem(idx) {
+        return None;
+    }
+
+    let mut tracer = Tracer::default();
+    let mut error = None;
+
+    loop {
+        match verifier.step_statement(&mut |x, v| tracer.action(x, v)) {
+            Ok(Some(())) => {}
+            Ok(None) => break,
+            Err(e) => {
+                error = Some(e);
+                break;
+            }
+        }
+    }
+
+    Some(Trace {
+        steps: tracer.steps,
+        error,
+    })
+}
+
+fn command_to_string(verifier: &Verifier, step: &Step) -> String {
+    let operand = step.command.operand as usize;
+    let term = || match verifier.get_term_name(operand) {
+        Some(name) => String::from(name),
+        None => format!("t{}", operand),
+    };
+    let theorem = || match verifier.get_theorem_name(operand) {
+        Some(name) => String::from(name),
+        None => format!("thm{}", operand),
+    };
+
+    let mut out = match step.command.opcode {
+        Proof::End => String::from("end"),
+        Proof::Ref => format!("ref h{}", operand),
+        Proof::Dummy => match verifier.get_sort_name(operand) {
+            Some(name) => format!("dummy {}", name),
+            None => format!("dummy s{}", operand),
+        },
+        Proof::Term | Proof::TermSave => format!("term {}", term()),
+        Proof::Thm | Proof::ThmSave => format!("thm {}", theorem()),
+        Proof::Hyp => String::from("hyp"),
+        Proof::Conv => String::from("conv"),
+        Proof::Refl => String::from("refl"),
+        Proof::Symm => String::from("symm"),
+        Proof::Cong => String::from("cong"),
+        Proof::Unfold => String::from("unfold"),
+        Proof::ConvCut => String::from("conv cut"),
+        Proof::ConvRef => format!("conv ref {}", operand),
+        Proof::ConvSave => String::from("conv save"),
+        Proof::Save => String::from("save"),
+    };
+
+    if let Some(slot) = step.saved {
+        let _ = write!(out, " > h{}", slot);
+    }
+
+    out
+}
+
+/// Lists the steps one per line with the step number, the steps it uses, the
+/// command and its result, with `expr` printing the expressions.
+pub fn listing<F: Fn(&Expr) -> String>(verifier: &Verifier, trace: &Trace, expr: F) -> String {
+    let rows: Vec<_> = trace
+        .steps
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let uses: Vec<_> = step.uses.iter().map(|x| format!("{}", x)).collect();
+            let result = match &step.result {
+                Some(entry) => entry.to_string_with(&expr),
+                None => String::new(),
+            };
+
+            (
+                format!("{}", i),
+                uses.join(","),
+                command_to_string(verifier, step),
+                result,
+            )
+        })
+        .collect();
+
+    let width = |f: fn(&(String, String, String, String)) -> &String| {
+        rows.iter().map(|x| f(x).len()).max().unwrap_or(0)
+    };
+    let widths = [width(|x| &x.0), width(|x| &x.1), width(|x| &x.2)];
+
+    let mut out = String::new();
+
+    for (step, uses, command, result) in &rows {
+        let line = format!(
+            "{:>w0$}  {:w1$}  {:w2$}  {}",
+            step,
+            uses,
+            command,
+            result,
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2]
+        );
+
+        out += line.trim_end();
+        out.push('\n');
+    }
+
+    if let Some(e) = &trace.error {
+        let _ = writeln!(out, "failed: {:?}", e);
+    }
+
+    out
+}
@@ -0,0 +1,295 @@
+//! A full-screen proof explorer, built with `--features tui`.
+//!
+//! The left pane lists the theorems whose names contain the filter, which is
+//! edited by typing. The right pane shows the statement of the selected
+//! theorem, and the proof heap, the proof stack and the hypothesis stack of
+//! the kernel while its proof is stepped through.
+//!
+//! ```text
+//! type        filter the theorems by name
+//! up, down    select a theorem
+//! enter       start verifying the selected theorem
+//! right       take one step
+//! space       keep stepping until the proof is done or space is pressed
+//! esc         quit
+//! ```
+
+use crate::expr::display_entry;
+use crate::kernel::context::PackedPtr;
+use crate::Verifier;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::widgets::{Block, List, ListState, Paragraph};
+use ratatui::Frame;
+use std::io;
+use std::time::Duration;
+
+/// The time between two steps while stepping continuously.
+const STEP_INTERVAL: Duration = Duration::from_millis(100);
+
+pub struct Explorer {
+    verifier: Verifier,
+    filter: String,
+    /// The theorems that match the filter.
+    theorems: Vec<usize>,
+    /// The position of the selected theorem in `theorems`.
+    selected: usize,
+    /// The theorem that is being verified, and whether it is done.
+    current: Option<(usize, bool)>,
+    steps: usize,
+    status: String,
+    playing: bool,
+    quit: bool,
+}
+
+impl Explorer {
+    pub fn new(verifier: Verifier) -> Explorer {
+        let mut explorer = Explorer {
+            verifier,
+            filter: String::new(),
+            theorems: Vec::new(),
+            selected: 0,
+            current: None,
+            steps: 0,
+            status: "enter starts the selected theorem".to_string(),
+            playing: false,
+            quit: false,
+        };
+
+        explorer.apply_filter();
+        explorer
+    }
+
+    fn theorem_name(&self, idx: usize) -> String {
+        match self.verifier.get_theorem_name(idx) {
+            Some(name) => name.to_string(),
+            None => format!("thm{}", idx),
+        }
+    }
+
+    fn apply_filter(&mut self) {
+        let previous = self.theorems.get(self.selected).copied();
+
+        self.theorems = (0..self.verifier.table.theorems.len())
+            .filter(|x| self.theorem_name(*x).contains(&self.filter))
+            .collect();
+
+        self.selected = previous
+            .and_then(|x| self.theorems.iter().position(|y| *y == x))
+            .unwrap_or(0);
+    }
+
+    fn select(&mut self, delta: isize) {
+        let last = self.theorems.len().saturating_sub(1) as isize;
+        self.selected = (self.selected as isize + delta).clamp(0, last) as usize;
+    }
+
+    fn start(&mut self) {
+        if let Some(idx) = self.theorems.get(self.selected).copied() {
+            self.verifier.seek_theorem(idx);
+            self.current = Some((idx, false));
+            self.steps = 0;
+            self.status = format!("started {}", self.theorem_name(idx));
+        }
+    }
+
+    /// Takes one step in the theorem that is being verified.
+    pub fn step(&mut self) {
+        let idx = match self.current {
+            Some((idx, false)) => idx,
+            _ => {
+                self.playing = false;
+                return;
+            }
+        };
+
+        let mut action = String::new();
+
+        let result = self
+            .verifier
+            .step_statement(&mut |x, _| action = format!("{:?}", x));
+
+        self.steps += 1;
+
+        match result {
+            Ok(Some(())) => self.status = action,
+            Ok(None) => {
+                self.current = Some((idx, true));
+                self.playing = false;
+                self.status = format!("{} is verified", self.theorem_name(idx));
+            }
+            Err(e) => {
+                self.current = Some((idx, true));
+                self.playing = false;
+                self.status = format!("{} fails: {:?}", self.theorem_name(idx), e);
+            }
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.quit = true,
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => self.quit = true,
+            KeyCode::Char(' ') => self.playing = !self.playing,
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.apply_filter();
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.apply_filter();
+            }
+            KeyCode::Up => self.select(-1),
+            KeyCode::Down => self.select(1),
+            KeyCode::PageUp => self.select(-20),
+            KeyCode::PageDown => self.select(20),
+            KeyCode::Enter => self.start(),
+            KeyCode::Right => self.step(),
+            _ => {}
+        }
+    }
+
+    fn entries(&self, entries: &[PackedPtr]) -> Vec<String> {
+        let store = self.verifier.context.get_store();
+        let names = |x: u32| self.verifier.get_term_name(x as usize);
+
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, x)| format!("{}: {}", i, display_entry(store, *x, names)))
+            .collect()
+    }
+
+    fn statement(&self, idx: usize) -> Vec<String> {
+        let names = |x: u32| self.verifier.get_term_name(x as usize);
+
+        match self.verifier.get_theorem_statement(idx as u32) {
+            Ok((hyps, conclusion)) => hyps
+                .iter()
+                .map(|x| format!("   {}", x.to_display(names)))
+                .chain(Some(format!("|- {}", conclusion.to_display(names))))
+                .collect(),
+            Err(e) => vec![format!("{:?}", e)],
+        }
+    }
+
+    /// Shows the last lines that fit, so that the top of a stack is visible.
+    fn pane(frame: &mut Frame, area: Rect, title: &str, lines: Vec<String>) {
+        let height = area.height.saturating_sub(2) as usize;
+        let scroll = lines.len().saturating_sub(height) as u16;
+
+        let paragraph = Paragraph::new(lines.join("\n"))
+            .block(Block::bordered().title(title.to_string()))
+            .scroll((scroll, 0));
+
+        frame.render_widget(paragraph, area);
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        let [left, right] =
+            Layout::horizontal([Constraint::Percentage(30), Constraint::Percentage(70)])
+                .areas(frame.area());
+        let [filter, list] =
+            Layout::vertical([Constraint::Length(3), Constraint::Min(0)]).areas(left);
+
+        frame.render_widget(
+            Paragraph::new(self.filter.as_str()).block(Block::bordered().title("filter")),
+            filter,
+        );
+
+        let items: Vec<_> = self
+            .theorems
+            .iter()
+            .map(|x| match self.current {
+                Some((idx, _)) if idx == *x => format!("> {}", self.theorem_name(*x)),
+                _ => format!("  {}", self.theorem_name(*x)),
+            })
+            .collect();
+
+        let title = format!(
+            "theorems {}/{}",
+            self.theorems.len(),
+            self.verifier.table.theorems.len()
+        );
+        let mut state = ListState::default().with_selected(Some(self.selected));
+
+        frame.render_stateful_widget(
+            List::new(items)
+                .block(Block::bordered().title(title))
+                .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+            list,
+            &mut state,
+        );
+
+        let statement = match self.theorems.get(self.selected) {
+            Some(idx) => self.statement(*idx),
+            None => Vec::new(),
+        };
+
+        let [statement_area, stacks, status] = Layout::vertical([
+            Constraint::Length(statement.len() as u16 + 2),
+            Constraint::Min(0),
+            Constraint::Length(3),
+        ])
+        .areas(right);
+
+        Explorer::pane(frame, statement_area, "statement", statement);
+
+        let [heap, proof, hyps] = Layout::horizontal([Constraint::Ratio(1, 3); 3]).areas(stacks);
+        let context = &self.verifier.context;
+
+        if self.current.is_some() {
+            let heap_entries = self.entries(context.get_proof_heap().as_slice());
+            let proof_entries = self.entries(context.get_proof_stack().as_slice());
+            let hyp_entries = self.entries(context.get_hyp_stack().as_slice());
+
+            Explorer::pane(frame, heap, "heap", heap_entries);
+            Explorer::pane(frame, proof, "proof stack", proof_entries);
+            Explorer::pane(frame, hyps, "hypothesis stack", hyp_entries);
+        } else {
+            Explorer::pane(frame, heap, "heap", Vec::new());
+            Explorer::pane(frame, proof, "proof stack", Vec::new());
+            Explorer::pane(frame, hyps, "hypothesis stack", Vec::new());
+        }
+
+        let title = format!("step {}", self.steps);
+        frame.render_widget(
+            Paragraph::new(self.status.as_str()).block(Block::bordered().title(title)),
+            status,
+        );
+    }
+}
+
+/// Runs the explorer in the terminal until it is quit.
+pub fn run(explorer: &mut Explorer) -> io::Result<()> {
+    let mut terminal = ratatui::init();
+
+    let result = (|| {
+        while !explorer.quit {
+            terminal.draw(|frame| explorer.draw(frame))?;
+
+            let timeout = if explorer.playing {
+                STEP_INTERVAL
+            } else {
+                Duration::from_secs(60)
+            };
+
+            if event::poll(timeout)? {
+                if let Event::Key(key) = event::read()? {
+                    if key.kind == KeyEventKind::Press {
+                        explorer.handle_key(key);
+                    }
+                }
+            } else if explorer.playing {
+                explorer.step();
+            }
+        }
+
+        Ok(())
+    })();
+
+    ratatui::restore();
+
+    result
+}
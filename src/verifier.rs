@@ -1,28 +1,96 @@
+use crate::expr::Expr;
 use crate::kernel::{
-    context::PackedPtr, stream::proof, Context, KResult, State, Stepper, Store_, Table, Table_,
-    Term, Theorem, Var_,
+    context::{PackedPtr, Ptr},
+    error::Kind,
+    opcode::{self, Command},
+    stream::proof,
+    Context, KResult, State, Stepper, Store, Store_, Table, Table_, Term, Theorem, Var, Var_,
 };
-use crate::mmb_visitor::MmbVisitor;
+use crate::mmb_visitor::{Capacity, MmbVisitor};
 use crate::statement_iter::StatementOwned;
 use mmb_parser::Mmb;
 
 use crate::kernel::stream::statement::Action;
-use std::collections::HashMap;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::convert::TryInto;
 
 #[derive(Default)]
 struct Index {
-    data: HashMap<String, usize>,
+    data: BTreeMap<String, usize>,
     names: Vec<String>,
 }
 
-impl mmb_parser::index::Visitor for Index {
-    fn visit<'a>(&mut self, idx: usize, _ptr: u64, name: &'a [u8]) {
-        let stripped = &name[..(name.len() - 1)];
+impl Index {
+    /// Reads an index table, which points to an entry for every sort, term
+    /// or theorem, with the name after the first 37 bytes of the entry. A
+    /// file without an index, or with a malformed one, has no names.
+    fn read(file: &[u8], table: &[u8]) -> Index {
+        let mut index = Index::default();
+
+        // the pointer to the index is at offset 32 of the header
+        if file.get(32..40).is_none_or(|x| x.iter().all(|x| *x == 0)) {
+            return index;
+        }
+
+        for (idx, ptr) in table.chunks_exact(8).enumerate() {
+            let ptr = u64::from_le_bytes(ptr.try_into().unwrap()) as usize;
+
+            let name = file
+                .get(ptr.saturating_add(37)..)
+                .and_then(|x| Some(&x[..x.iter().position(|c| *c == 0)?]))
+                .and_then(|x| core::str::from_utf8(x).ok());
+
+            match name {
+                Some(name) => {
+                    index.data.insert(name.to_string(), idx);
+                    index.names.push(name.to_string());
+                }
+                None => return Index::default(),
+            }
+        }
+
+        index
+    }
+}
+
+/// A statement of the file, together with the index of the sort, term or
+/// theorem it declares.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct StatementInfo {
+    pub kind: opcode::Statement,
+    pub index: usize,
+}
+
+impl StatementInfo {
+    /// Local definitions and theorems are not exported by the file.
+    ///
+    /// `opcode::Statement::LocalTerm` is the local theorem statement.
+    pub fn is_local(&self) -> bool {
+        matches!(
+            self.kind,
+            opcode::Statement::LocalDef | opcode::Statement::LocalTerm
+        )
+    }
+
+    pub fn is_sort(&self) -> bool {
+        matches!(self.kind, opcode::Statement::Sort)
+    }
 
-        let string = std::str::from_utf8(stripped).unwrap().to_string();
+    pub fn is_term(&self) -> bool {
+        matches!(
+            self.kind,
+            opcode::Statement::TermDef | opcode::Statement::LocalDef
+        )
+    }
 
-        self.data.insert(string.clone(), idx);
-        self.names.push(string);
+    pub fn is_theorem(&self) -> bool {
+        matches!(
+            self.kind,
+            opcode::Statement::Axiom | opcode::Statement::Thm | opcode::Statement::LocalTerm
+        )
     }
 }
 
@@ -30,6 +98,8 @@ pub struct Verifier {
     pub table: Table_,
     pub context: Context<Store_>,
     pub state: State,
+    sort_index: Index,
+    term_index: Index,
     theorem_index: Index,
     stepper: Stepper<StatementOwned, Var_>,
 }
@@ -38,12 +108,12 @@ impl Verifier {
     pub fn new(data: &[u8]) -> Option<Verifier> {
         let data = Mmb::from(data)?;
 
-        let mut visitor = MmbVisitor::new();
+        let mut visitor = MmbVisitor::with_capacity(Capacity::from_mmb(&data));
         data.visit(&mut visitor).ok()?;
 
-        let mut index = Index::default();
-
-        data.visit_theorem_index(&mut index);
+        let sort_index = Index::read(data.file, data.sort_index);
+        let term_index = Index::read(data.file, data.term_index);
+        let theorem_index = Index::read(data.file, data.theorem_index);
 
         let (table, stream) = visitor.into_table_owned();
 
@@ -54,10 +124,28 @@ impl Verifier {
             stepper,
             context: Context::default(),
             state: State::default(),
-            theorem_index: index,
+            sort_index,
+            term_index,
+            theorem_index,
         })
     }
 
+    pub fn get_sort_index(&self, name: &str) -> Option<usize> {
+        self.sort_index.data.get(name).cloned()
+    }
+
+    pub fn get_sort_name(&self, idx: usize) -> Option<&str> {
+        self.sort_index.names.get(idx).map(|x| x.as_str())
+    }
+
+    pub fn get_term_index(&self, name: &str) -> Option<usize> {
+        self.term_index.data.get(name).cloned()
+    }
+
+    pub fn get_term_name(&self, idx: usize) -> Option<&str> {
+        self.term_index.names.get(idx).map(|x| x.as_str())
+    }
+
     pub fn get_theorem_index(&self, name: &str) -> Option<usize> {
         self.theorem_index.data.get(name).cloned()
     }
@@ -161,6 +249,151 @@ impl Verifier {
         self.state = stream.seek_to(idx);
     }
 
+    /// Returns the index in `get_statements` of the statement that is being
+    /// verified, or of the next one between statements.
+    pub fn get_position(&self) -> usize {
+        let position = self.stepper.get_stream().get_position();
+
+        if self.stepper.is_state_normal() {
+            position
+        } else {
+            position - 1
+        }
+    }
+
+    /// Returns every statement of the file in order.
+    pub fn get_statements(&self) -> Vec<StatementInfo> {
+        let mut counts = [0; 3];
+
+        self.stepper
+            .get_stream()
+            .get_statements()
+            .iter()
+            .filter_map(|x| {
+                let kind = x.code;
+                let counter = match kind {
+                    opcode::Statement::End => return None,
+                    opcode::Statement::Sort => &mut counts[0],
+                    opcode::Statement::TermDef | opcode::Statement::LocalDef => &mut counts[1],
+                    _ => &mut counts[2],
+                };
+
+                let index = *counter;
+                *counter += 1;
+
+                Some(StatementInfo { kind, index })
+            })
+            .collect()
+    }
+
+    /// Returns the statement that declares term `idx`.
+    pub fn get_term_info(&self, idx: usize) -> Option<StatementInfo> {
+        let stream = self.stepper.get_stream();
+        let statement = stream.term_indices.get(idx)?;
+        let kind = stream.get_statements().get(*statement)?.code;
+
+        Some(StatementInfo { kind, index: idx })
+    }
+
+    /// Returns the statement that declares theorem `idx`.
+    pub fn get_theorem_info(&self, idx: usize) -> Option<StatementInfo> {
+        let stream = self.stepper.get_stream();
+        let statement = stream.theorem_indices.get(idx)?;
+        let kind = stream.get_statements().get(*statement)?.code;
+
+        Some(StatementInfo { kind, index: idx })
+    }
+
+    /// Returns the proof stream of the statement `idx` of `get_statements`,
+    /// without the end command.
+    pub fn get_proof(&self, idx: usize) -> Option<&[Command<opcode::Proof>]> {
+        self.stepper.get_stream().get_proof(idx)
+    }
+
+    /// Returns the name of the sort, term or theorem declared by a statement.
+    pub fn get_statement_name(&self, statement: &StatementInfo) -> Option<&str> {
+        if statement.is_sort() {
+            self.get_sort_name(statement.index)
+        } else if statement.is_term() {
+            self.get_term_name(statement.index)
+        } else {
+            self.get_theorem_name(statement.index)
+        }
+    }
+
+    /// Returns the terms and the theorems that the proof of theorem `idx`
+    /// refers to, without duplicates.
+    pub fn get_dependencies(&self, idx: usize) -> (Vec<usize>, Vec<usize>) {
+        let statement = self
+            .get_statements()
+            .iter()
+            .position(|x| x.is_theorem() && x.index == idx);
+
+        let mut terms = Vec::new();
+        let mut theorems = Vec::new();
+
+        for i in statement
+            .and_then(|x| self.get_proof(x))
+            .unwrap_or_default()
+        {
+            match i.opcode {
+                opcode::Proof::Term | opcode::Proof::TermSave => terms.push(i.operand as usize),
+                opcode::Proof::Thm | opcode::Proof::ThmSave => theorems.push(i.operand as usize),
+                _ => {}
+            }
+        }
+
+        for i in [&mut terms, &mut theorems].iter_mut() {
+            i.sort_unstable();
+            i.dedup();
+        }
+
+        (terms, theorems)
+    }
+
+    /// Returns the axioms that the proof of theorem `idx` depends on,
+    /// transitively, in the order of the file.
+    pub fn get_axioms(&self, idx: usize) -> Vec<usize> {
+        let statements = self.get_statements();
+        let theorems: Vec<_> = (0..statements.len())
+            .filter(|x| statements[*x].is_theorem())
+            .collect();
+
+        let mut seen = vec![false; theorems.len()];
+        let mut todo = vec![idx];
+        let mut axioms = Vec::new();
+
+        while let Some(thm) = todo.pop() {
+            match seen.get_mut(thm) {
+                Some(x) if !*x => *x = true,
+                _ => continue,
+            }
+
+            let statement = theorems[thm];
+
+            if statements[statement].kind == opcode::Statement::Axiom && thm != idx {
+                axioms.push(thm);
+            }
+
+            for i in self.get_proof(statement).unwrap_or_default() {
+                if let opcode::Proof::Thm | opcode::Proof::ThmSave = i.opcode {
+                    todo.push(i.operand as usize);
+                }
+            }
+        }
+
+        axioms.sort_unstable();
+        axioms
+    }
+
+    /// Returns the statements that are exported by the file, which is every
+    /// statement except for local definitions and theorems.
+    pub fn get_public_statements(&self) -> Vec<StatementInfo> {
+        let mut statements = self.get_statements();
+        statements.retain(|x| !x.is_local());
+        statements
+    }
+
     pub fn create_theorem_application<'a>(
         &self,
         id: u32,
@@ -217,6 +450,96 @@ impl Verifier {
         Ok((args, context.get_hyp_stack().as_slice(), *res))
     }
 
+    /// Returns the hypotheses and the conclusion of a theorem as expression
+    /// trees, with variables numbered by binder position.
+    pub fn get_theorem_statement(&self, id: u32) -> KResult<(Vec<Expr>, Expr)> {
+        let mut context = Context::<Store_>::default();
+
+        let (_, hyps, conclusion) = self.create_theorem_application(id, &mut context)?;
+        let hyps = hyps.to_vec();
+        let store = context.get_store();
+
+        let hyps = hyps
+            .iter()
+            .map(|x| Expr::from_store(store, *x))
+            .collect::<KResult<Vec<_>>>()?;
+        let conclusion = Expr::from_store(store, conclusion)?;
+
+        Ok((hyps, conclusion))
+    }
+
+    /// Returns the value of a definition and the sorts of its dummy
+    /// variables, or `None` for a term.
+    ///
+    /// Variables are numbered by binder position, followed by the dummy
+    /// variables in order of their first occurrence.
+    pub fn get_definition_value(&self, id: u32) -> KResult<Option<(Expr, Vec<u8>)>> {
+        let f = |x: u32| {
+            let term = self.table.get_term(x)?;
+            Some(term.get_binders().len() as u32)
+        };
+
+        let term = self
+            .table
+            .get_term(id)
+            .ok_or(crate::kernel::error::Kind::InvalidTerm)?;
+
+        if !term.is_definition() {
+            return Ok(None);
+        }
+
+        let unify = term.get_command_stream();
+        let unify = self
+            .table
+            .get_unify_commands(unify)
+            .ok_or(crate::kernel::error::Kind::InvalidUnifyCommandIndex)?;
+
+        let binders = term.get_binders();
+        let binders = self
+            .table
+            .get_binders(binders)
+            .ok_or(crate::kernel::error::Kind::InvalidBinderIndices)?;
+
+        let mut context = Context::<Store_>::default();
+        let state = State::from_table(&self.table);
+
+        context.allocate_binders(&self.table, state.get_current_sort(), binders)?;
+
+        let proof = trivial_compiler::unify_to_proof(binders.len() as u32, unify.iter(), f)
+            .map_err(|_| crate::kernel::error::Kind::InvalidTerm)?;
+
+        let mut stepper = proof::Stepper::new(true, state, proof.iter().cloned());
+
+        stepper.run(&mut context, &self.table)?;
+
+        let value = context
+            .get_proof_stack()
+            .peek()
+            .ok_or(crate::kernel::error::Kind::ProofStackUnderflow)?;
+
+        let store = context.get_store();
+        let value = Expr::from_store(store, *value)?;
+        let (value, dummies) = value.renumber_dummies(binders.len() as u16);
+
+        let dummies = dummies
+            .iter()
+            .map(|x| {
+                let ptr = context
+                    .get_proof_heap()
+                    .as_slice()
+                    .get(*x as usize)
+                    .ok_or(crate::kernel::error::Kind::InvalidHeapIndex)?;
+                let ty = store
+                    .get_type_of_expr(Ptr::from(*ptr))
+                    .ok_or(crate::kernel::error::Kind::InvalidStoreIndex)?;
+
+                Ok(ty.get_sort_idx())
+            })
+            .collect::<KResult<Vec<_>>>()?;
+
+        Ok(Some((value, dummies)))
+    }
+
     pub fn step<F: FnMut(Action, &Self)>(&mut self, f: &mut F) -> KResult<Option<()>> {
         let x = self
             .stepper
@@ -260,4 +583,22 @@ impl Verifier {
 
         Ok(())
     }
+
+    /// Verifies every statement from the start, and carries on with the next
+    /// statement after one fails. Returns the statements that failed.
+    pub fn find_failures(&mut self) -> Vec<(StatementInfo, Kind)> {
+        let statements = self.get_statements();
+        let mut failures = Vec::new();
+
+        self.seek(0);
+
+        for (i, statement) in statements.iter().enumerate() {
+            if let Err(e) = self.run_statement(&mut |_, _| {}) {
+                failures.push((*statement, e));
+                self.seek(i + 1);
+            }
+        }
+
+        failures
+    }
 }
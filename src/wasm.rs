@@ -0,0 +1,138 @@
+//! Bindings for JavaScript, built with `--features wasm` for `wasm32`.
+//!
+//! Structured results are built as JSON and handed to JavaScript as plain
+//! objects. Memory is reserved from the sizes in the header of the file, so
+//! small files only take little of the memory of the browser.
+
+use crate::mm0::{Mm0, Printer};
+use crate::Verifier;
+use serde_json::{json, Value};
+use wasm_bindgen::prelude::*;
+
+fn to_js(value: &Value) -> Result<JsValue, JsValue> {
+    js_sys::JSON::parse(&value.to_string())
+}
+
+fn error<E: core::fmt::Debug>(e: E) -> JsValue {
+    JsValue::from_str(&format!("{:?}", e))
+}
+
+#[wasm_bindgen(js_name = Verifier)]
+pub struct WasmVerifier {
+    inner: Verifier,
+}
+
+#[wasm_bindgen(js_class = Verifier)]
+impl WasmVerifier {
+    #[wasm_bindgen(constructor)]
+    pub fn new(data: &[u8]) -> Result<WasmVerifier, JsValue> {
+        let inner = Verifier::new(data).ok_or_else(|| error("InvalidFile"))?;
+
+        Ok(WasmVerifier { inner })
+    }
+
+    /// Verifies the remaining statements.
+    pub fn run(&mut self) -> Result<(), JsValue> {
+        self.inner.run(&mut |_, _| {}).map_err(error)
+    }
+
+    /// Takes one step, and returns whether there was anything left to do.
+    pub fn step(&mut self) -> Result<bool, JsValue> {
+        self.inner
+            .step(&mut |_, _| {})
+            .map(|x| x.is_some())
+            .map_err(error)
+    }
+
+    #[wasm_bindgen(js_name = sortName)]
+    pub fn sort_name(&self, idx: usize) -> Option<String> {
+        self.inner.get_sort_name(idx).map(|x| x.to_string())
+    }
+
+    #[wasm_bindgen(js_name = termName)]
+    pub fn term_name(&self, idx: usize) -> Option<String> {
+        self.inner.get_term_name(idx).map(|x| x.to_string())
+    }
+
+    #[wasm_bindgen(js_name = theoremName)]
+    pub fn theorem_name(&self, idx: usize) -> Option<String> {
+        self.inner.get_theorem_name(idx).map(|x| x.to_string())
+    }
+
+    #[wasm_bindgen(js_name = sortIndex)]
+    pub fn sort_index(&self, name: &str) -> Option<usize> {
+        self.inner.get_sort_index(name)
+    }
+
+    #[wasm_bindgen(js_name = termIndex)]
+    pub fn term_index(&self, name: &str) -> Option<usize> {
+        self.inner.get_term_index(name)
+    }
+
+    #[wasm_bindgen(js_name = theoremIndex)]
+    pub fn theorem_index(&self, name: &str) -> Option<usize> {
+        self.inner.get_theorem_index(name)
+    }
+
+    /// Returns every statement as `{ kind, index, local }`.
+    pub fn statements(&self) -> Result<JsValue, JsValue> {
+        let statements: Vec<_> = self
+            .inner
+            .get_statements()
+            .iter()
+            .map(|x| {
+                let kind = if x.is_sort() {
+                    "sort"
+                } else if x.is_term() {
+                    "term"
+                } else {
+                    "theorem"
+                };
+
+                json!({ "kind": kind, "index": x.index, "local": x.is_local() })
+            })
+            .collect();
+
+        to_js(&Value::from(statements))
+    }
+
+    /// Returns the hypotheses and the conclusion of a theorem as strings,
+    /// in the notation of `spec` if one is given and as s-expressions
+    /// otherwise.
+    #[wasm_bindgen(js_name = printTheorem)]
+    pub fn print_theorem(&self, id: u32, spec: Option<String>) -> Result<JsValue, JsValue> {
+        let (hyps, conclusion) = self.inner.get_theorem_statement(id).map_err(error)?;
+
+        let (hyps, conclusion): (Vec<String>, String) = match spec {
+            Some(spec) => {
+                let mm0 = Mm0::parse(&spec).map_err(error)?;
+                let printer = Printer::new(&self.inner, &mm0).map_err(error)?;
+                let vars = printer.theorem_vars(&self.inner, id);
+
+                (
+                    hyps.iter()
+                        .map(|x| printer.to_display(x, &vars).to_string())
+                        .collect(),
+                    printer.to_display(&conclusion, &vars).to_string(),
+                )
+            }
+            None => {
+                let names = |x: u32| self.inner.get_term_name(x as usize);
+
+                (
+                    hyps.iter()
+                        .map(|x| x.to_display(names).to_string())
+                        .collect(),
+                    conclusion.to_display(names).to_string(),
+                )
+            }
+        };
+
+        to_js(&json!({ "hypotheses": hyps, "conclusion": conclusion }))
+    }
+
+    /// Returns the whole file in the format of `dump::dump`.
+    pub fn dump(&self) -> Result<JsValue, JsValue> {
+        to_js(&crate::dump::dump(&self.inner).map_err(error)?)
+    }
+}
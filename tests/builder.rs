@@ -0,0 +1,143 @@
+mod common;
+
+use common::{logic, p, thm3_proof, u};
+use trivial_verifier::builder::{MmbBuilder, MAX_SORTS};
+use trivial_verifier::dump::dump;
+use trivial_verifier::expr::Expr;
+use trivial_verifier::kernel::opcode::{Proof, Unify};
+use trivial_verifier::kernel::Sort_;
+use trivial_verifier::{OneShotVerifier, Verifier, ZeroCopyVerifier};
+
+fn round_trip(data: &[u8]) -> Vec<u8> {
+    let verifier = Verifier::new(data).expect("the file can be parsed");
+    let builder = MmbBuilder::from_verifier(&verifier).expect("the file can be copied");
+
+    builder.to_bytes()
+}
+
+#[test]
+fn written_file_verifies() {
+    let data = logic(&thm3_proof()).to_bytes();
+
+    let mut verifier = Verifier::new(&data).unwrap();
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    assert_eq!(verifier.get_sort_name(0), Some("wff"));
+    assert_eq!(verifier.get_term_index("imp"), Some(1));
+    assert_eq!(verifier.get_theorem_index("thm3"), Some(3));
+
+    let (hyps, conclusion) = verifier.get_theorem_statement(3).unwrap();
+    let t = Expr::Term(0, Vec::new());
+
+    assert!(hyps.is_empty());
+    assert_eq!(conclusion, Expr::Term(1, vec![t.clone(), t]));
+
+    OneShotVerifier::new(&data)
+        .unwrap()
+        .run(&mut |_, _| {})
+        .unwrap();
+
+    ZeroCopyVerifier::new(&data)
+        .unwrap()
+        .run(&mut |_, _| {})
+        .unwrap();
+}
+
+#[test]
+fn invalid_proof_is_rejected() {
+    let mut proof = thm3_proof();
+    *proof.last_mut().unwrap() = p(Proof::Thm, 1);
+
+    let data = logic(&proof).to_bytes();
+
+    let mut verifier = Verifier::new(&data).unwrap();
+    assert!(verifier.run(&mut |_, _| {}).is_err());
+}
+
+#[test]
+fn round_trip_is_identical() {
+    let builder = logic(&thm3_proof());
+    let data = builder.to_bytes();
+
+    let verifier = Verifier::new(&data).unwrap();
+    assert_eq!(MmbBuilder::from_verifier(&verifier), Some(builder));
+
+    assert_eq!(round_trip(&data), data);
+}
+
+#[test]
+fn round_trip_preserves_the_database() {
+    let data = logic(&thm3_proof()).to_bytes();
+    let copy = round_trip(&data);
+
+    let before = dump(&Verifier::new(&data).unwrap()).unwrap();
+    let after = dump(&Verifier::new(&copy).unwrap()).unwrap();
+
+    assert_eq!(before, after);
+}
+
+#[test]
+fn round_trip_of_wide_operands_and_long_statements() {
+    let mut builder = logic(&thm3_proof());
+
+    // theorem indices above 255 need two byte operands
+    for i in 0..300 {
+        let prev = if i == 0 { 0 } else { 3 + i };
+        builder.add_theorem(
+            &format!("t{}", i),
+            &[],
+            &[u(Unify::Term, 0)],
+            &[p(Proof::Term, 0), p(Proof::Thm, prev)],
+            false,
+        );
+    }
+
+    // a proof stream of more than 255 bytes needs a longer statement header
+    let mut proof = vec![p(Proof::Term, 0), p(Proof::Thm, 303)];
+    proof.resize(402, p(Proof::Save, 0));
+
+    builder.add_theorem("long", &[], &[u(Unify::Term, 0)], &proof, false);
+
+    let data = builder.to_bytes();
+
+    let mut verifier = Verifier::new(&data).unwrap();
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    assert_eq!(verifier.get_theorem_index("long"), Some(304));
+    assert_eq!(round_trip(&data), data);
+}
+
+#[test]
+fn all_sorts_fit_in_a_file() {
+    let mut builder = MmbBuilder::new();
+
+    for i in 0..MAX_SORTS {
+        assert_eq!(builder.add_sort(&format!("s{}", i), Sort_(4)), i as u8);
+    }
+
+    // mmb-parser computes the size of the sort index in a `u8`, so the file
+    // is checked byte by byte
+    let data = builder.to_bytes();
+
+    assert_eq!(data[5] as usize, MAX_SORTS);
+    assert_eq!(&data[40..(40 + MAX_SORTS)], &[4; MAX_SORTS][..]);
+}
+
+#[test]
+#[should_panic(expected = "too many sorts")]
+fn sort_past_the_limit_is_refused() {
+    let mut builder = MmbBuilder::new();
+
+    for i in 0..=MAX_SORTS {
+        builder.add_sort(&format!("s{}", i), Sort_(0));
+    }
+}
+
+#[test]
+#[should_panic(expected = "too many sorts")]
+fn file_with_too_many_sorts_is_refused() {
+    let mut builder = MmbBuilder::new();
+    builder.sorts = vec![("s".to_string(), Sort_(0)); MAX_SORTS + 1];
+
+    builder.to_bytes();
+}
@@ -0,0 +1,111 @@
+mod common;
+
+use common::{logic, p, thm3_proof, u};
+use trivial_verifier::cache::{run, statement_hashes, Cache, Mode};
+use trivial_verifier::kernel::opcode::{Proof, Unify};
+use trivial_verifier::Verifier;
+
+fn broken_proof() -> Vec<u8> {
+    let mut proof = thm3_proof();
+    proof[12] = p(Proof::Ref, 0);
+
+    logic(&proof).to_bytes()
+}
+
+#[test]
+fn every_statement_has_a_hash() {
+    let data = logic(&thm3_proof()).to_bytes();
+    let hashes = statement_hashes(&data).unwrap();
+    let verifier = Verifier::new(&data).unwrap();
+
+    assert_eq!(hashes.len(), verifier.get_statements().len());
+    assert_eq!(hashes, statement_hashes(&data).unwrap());
+}
+
+#[test]
+fn changing_a_proof_command_changes_only_that_statement() {
+    let good = statement_hashes(&logic(&thm3_proof()).to_bytes()).unwrap();
+    let bad = statement_hashes(&broken_proof()).unwrap();
+
+    let changed: Vec<_> = (0..good.len()).filter(|x| good[*x] != bad[*x]).collect();
+
+    assert_eq!(changed, [7]);
+}
+
+#[test]
+fn changing_a_statement_changes_the_statements_using_it() {
+    let good = statement_hashes(&logic(&thm3_proof()).to_bytes()).unwrap();
+
+    let mut builder = logic(&thm3_proof());
+    builder.theorems[0].unify = vec![u(Unify::Term, 1), u(Unify::Term, 0), u(Unify::Term, 0)];
+    let changed = statement_hashes(&builder.to_bytes()).unwrap();
+
+    let changed: Vec<_> = (0..good.len())
+        .filter(|x| good[*x] != changed[*x])
+        .collect();
+
+    // `ax_t` and `thm3`, whose proof applies it
+    assert_eq!(changed, [4, 7]);
+}
+
+#[test]
+fn cached_statements_are_skipped() {
+    let data = logic(&thm3_proof()).to_bytes();
+    let hashes = statement_hashes(&data).unwrap();
+    let mut cache = Cache::new();
+
+    let mut verifier = Verifier::new(&data).unwrap();
+    assert_eq!(
+        run(&mut verifier, &hashes, &mut cache, Mode::TrustCache),
+        Ok(0)
+    );
+    assert_eq!(cache.len(), hashes.len());
+
+    let mut verifier = Verifier::new(&data).unwrap();
+    assert_eq!(
+        run(&mut verifier, &hashes, &mut cache, Mode::TrustCache),
+        Ok(hashes.len())
+    );
+
+    let mut verifier = Verifier::new(&data).unwrap();
+    assert_eq!(
+        run(&mut verifier, &hashes, &mut cache, Mode::FullCheck),
+        Ok(0)
+    );
+}
+
+#[test]
+fn changed_proof_is_not_trusted() {
+    let data = logic(&thm3_proof()).to_bytes();
+    let mut cache = Cache::new();
+
+    let mut verifier = Verifier::new(&data).unwrap();
+    run(
+        &mut verifier,
+        &statement_hashes(&data).unwrap(),
+        &mut cache,
+        Mode::TrustCache,
+    )
+    .unwrap();
+
+    let data = broken_proof();
+    let hashes = statement_hashes(&data).unwrap();
+    let mut verifier = Verifier::new(&data).unwrap();
+
+    assert!(run(&mut verifier, &hashes, &mut cache, Mode::TrustCache).is_err());
+    assert!(!cache.contains(&hashes[7]));
+}
+
+#[test]
+fn cache_is_written_and_read_back() {
+    let data = logic(&thm3_proof()).to_bytes();
+    let mut cache = Cache::new();
+
+    for i in statement_hashes(&data).unwrap() {
+        cache.insert(i);
+    }
+
+    assert_eq!(Cache::parse(&cache.to_string()), Some(cache.clone()));
+    assert_eq!(Cache::parse("trivial-verifier-cache 0.0.0\n"), None);
+    assert_eq!(Cache::parse(""), None);
+}
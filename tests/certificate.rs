@@ -0,0 +1,90 @@
+mod common;
+
+use common::{logic, p, thm3_proof, u};
+use trivial_verifier::certificate::{Certificate, FORMAT, KERNEL_VERSION, VERSION};
+use trivial_verifier::kernel::opcode::{Proof, Unify};
+use trivial_verifier::Verifier;
+
+fn certificate(data: &[u8]) -> Certificate {
+    let mut verifier = Verifier::new(data).unwrap();
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    Certificate::new(data, &verifier).unwrap()
+}
+
+#[test]
+fn certificate_lists_the_used_axioms() {
+    let certificate = certificate(&logic(&thm3_proof()).to_bytes());
+
+    assert_eq!(certificate.verifier, env!("CARGO_PKG_VERSION"));
+    assert_eq!(certificate.kernel, KERNEL_VERSION);
+    assert_eq!(
+        (certificate.sorts, certificate.terms, certificate.theorems),
+        (1, 3, 4)
+    );
+    assert_eq!(certificate.axioms, ["ax_t", "ax1", "mp"]);
+    assert_eq!(certificate.input.len(), 64);
+}
+
+#[test]
+fn certificate_is_written_and_read_back() {
+    let certificate = certificate(&logic(&thm3_proof()).to_bytes());
+    let json = certificate.to_json();
+
+    assert_eq!(json["format"], FORMAT);
+    assert_eq!(json["version"], VERSION);
+    assert_eq!(Certificate::from_json(&json), Some(certificate));
+
+    let mut old = json.clone();
+    old["version"] = 2.into();
+    assert_eq!(Certificate::from_json(&old), None);
+
+    let mut broken = json;
+    broken["axioms"] = 1.into();
+    assert_eq!(Certificate::from_json(&broken), None);
+}
+
+#[test]
+fn mismatches_name_the_fields() {
+    let expected = certificate(&logic(&thm3_proof()).to_bytes());
+    assert!(expected.mismatches(&expected).is_empty());
+
+    let mut other = expected.clone();
+    other.verifier = "0.0.0".to_string();
+    assert_eq!(expected.mismatches(&other), ["verifier"]);
+
+    let mut other = expected.clone();
+    other.kernel = "0.8.0".to_string();
+    assert_eq!(expected.mismatches(&other), ["kernel"]);
+
+    let mut other = expected.clone();
+    other.axioms.pop();
+    assert_eq!(expected.mismatches(&other), ["axioms"]);
+
+    // the names are part of the exported statements
+    let mut builder = logic(&thm3_proof());
+    builder.theorems[3].name = "thm4".to_string();
+    let other = certificate(&builder.to_bytes());
+
+    assert_eq!(expected.mismatches(&other), ["input", "statements"]);
+
+    let mut builder = logic(&thm3_proof());
+    builder.add_axiom("ax2", &[], &[u(Unify::Term, 0)], &[p(Proof::Term, 0)]);
+    let other = certificate(&builder.to_bytes());
+
+    assert_eq!(
+        expected.mismatches(&other),
+        ["input", "counts", "statements"]
+    );
+}
+
+#[test]
+fn kernel_version_is_the_resolved_one() {
+    let lock = include_str!("../Cargo.lock");
+    let entry = "name = \"trivial-kernel\"\nversion = \"";
+    let start = lock.find(entry).unwrap() + entry.len();
+    let version = lock[start..].split('"').next().unwrap();
+
+    assert_eq!(KERNEL_VERSION, version);
+    assert_ne!(KERNEL_VERSION, "unknown");
+}
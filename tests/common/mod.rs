@@ -0,0 +1,142 @@
+//! A small file shared by the tests.
+
+// every test uses only some of the helpers
+#![allow(dead_code)]
+
+use trivial_verifier::builder::MmbBuilder;
+use trivial_verifier::kernel::opcode::{Command, Proof, Unify};
+use trivial_verifier::kernel::{Sort_, Var, Var_};
+
+pub fn u(opcode: Unify, operand: u32) -> Command<Unify> {
+    Command { opcode, operand }
+}
+
+pub fn p(opcode: Proof, operand: u32) -> Command<Proof> {
+    Command { opcode, operand }
+}
+
+/// A small propositional logic with `t`, implication, modus ponens and one
+/// theorem proving `t -> t`.
+pub fn logic(thm3: &[Command<Proof>]) -> MmbBuilder {
+    let mut builder = MmbBuilder::new();
+    let wff = Var_::new(0, 0, false);
+
+    builder.add_sort("wff", Sort_(4));
+    builder.add_term("t", &[], wff);
+    builder.add_term("imp", &[wff, wff], wff);
+
+    builder.add_def(
+        "tt",
+        &[],
+        wff,
+        &[u(Unify::Term, 1), u(Unify::Term, 0), u(Unify::Term, 0)],
+        &[p(Proof::Term, 0), p(Proof::Term, 0), p(Proof::Term, 1)],
+        true,
+    );
+
+    builder.add_axiom("ax_t", &[], &[u(Unify::Term, 0)], &[p(Proof::Term, 0)]);
+
+    builder.add_axiom(
+        "ax1",
+        &[wff, wff],
+        &[
+            u(Unify::Term, 1),
+            u(Unify::Ref, 0),
+            u(Unify::Term, 1),
+            u(Unify::Ref, 1),
+            u(Unify::Ref, 0),
+        ],
+        &[
+            p(Proof::Ref, 0),
+            p(Proof::Ref, 1),
+            p(Proof::Ref, 0),
+            p(Proof::Term, 1),
+            p(Proof::Term, 1),
+        ],
+    );
+
+    builder.add_axiom(
+        "mp",
+        &[wff, wff],
+        &[
+            u(Unify::Ref, 1),
+            u(Unify::Hyp, 0),
+            u(Unify::Term, 1),
+            u(Unify::Ref, 0),
+            u(Unify::Ref, 1),
+            u(Unify::Hyp, 0),
+            u(Unify::Ref, 0),
+        ],
+        &[
+            p(Proof::Ref, 0),
+            p(Proof::Hyp, 0),
+            p(Proof::Ref, 0),
+            p(Proof::Ref, 1),
+            p(Proof::Term, 1),
+            p(Proof::Hyp, 0),
+            p(Proof::Ref, 1),
+        ],
+    );
+
+    builder.add_theorem(
+        "thm3",
+        &[],
+        &[u(Unify::Term, 1), u(Unify::Term, 0), u(Unify::Term, 0)],
+        thm3,
+        false,
+    );
+
+    builder
+}
+
+pub fn thm3_proof() -> Vec<Command<Proof>> {
+    vec![
+        p(Proof::TermSave, 0),
+        p(Proof::Thm, 0),
+        p(Proof::Ref, 0),
+        p(Proof::Ref, 0),
+        p(Proof::Ref, 0),
+        p(Proof::Ref, 0),
+        p(Proof::Ref, 0),
+        p(Proof::TermSave, 1),
+        p(Proof::Term, 1),
+        p(Proof::Thm, 1),
+        p(Proof::Ref, 0),
+        p(Proof::Ref, 1),
+        p(Proof::Ref, 1),
+        p(Proof::Thm, 2),
+    ]
+}
+
+/// Clears the pointer to the index in the header, which leaves the file
+/// without names.
+pub fn without_index(mut data: Vec<u8>) -> Vec<u8> {
+    data[32..40].copy_from_slice(&[0; 8]);
+    data
+}
+
+/// A proof of `thm3` that leaves an extra expression on the stack.
+pub fn unfinished_thm3_proof() -> Vec<Command<Proof>> {
+    let mut proof = thm3_proof();
+    proof.insert(0, p(Proof::Term, 0));
+    proof
+}
+
+/// The fixture with a failing `thm3`, a `thm4` whose proof uses `thm3`, and a
+/// `thm5` that fails on its own. Each of them proves `t -> t`.
+pub fn failing_logic() -> MmbBuilder {
+    let mut builder = logic(&unfinished_thm3_proof());
+    let unify = [u(Unify::Term, 1), u(Unify::Term, 0), u(Unify::Term, 0)];
+
+    let thm4 = [
+        p(Proof::Term, 0),
+        p(Proof::Term, 0),
+        p(Proof::Term, 1),
+        p(Proof::Thm, 3),
+    ];
+
+    builder.add_theorem("thm4", &[], &unify, &thm4, false);
+    builder.add_theorem("thm5", &[], &unify, &unfinished_thm3_proof(), false);
+
+    builder
+}
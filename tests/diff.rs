@@ -0,0 +1,164 @@
+mod common;
+
+use common::{logic, p, thm3_proof, u};
+use trivial_verifier::builder::MmbBuilder;
+use trivial_verifier::diff::{diff, Change, Diff, Difference};
+use trivial_verifier::kernel::opcode::{self, Proof, Unify};
+use trivial_verifier::kernel::{Sort_, Var, Var_};
+use trivial_verifier::Verifier;
+
+/// The statement of `thm3` in the fixture.
+const THM3: usize = 7;
+
+/// Compares the fixture with a copy changed by `edit`.
+fn diff_with<F: FnOnce(&mut MmbBuilder)>(edit: F) -> Diff {
+    let old = logic(&thm3_proof());
+    let mut new = old.clone();
+
+    edit(&mut new);
+
+    let old = Verifier::new(&old.to_bytes()).unwrap();
+    let new = Verifier::new(&new.to_bytes()).unwrap();
+
+    diff(&old, &new).unwrap()
+}
+
+fn difference(name: &str, change: Change, public: bool) -> Difference {
+    Difference {
+        name: name.to_string(),
+        change,
+        public,
+    }
+}
+
+#[test]
+fn identical_files_have_no_differences() {
+    let diff = diff_with(|_| {});
+
+    assert!(diff.is_empty());
+    assert_eq!(diff.breaking().count(), 0);
+}
+
+#[test]
+fn added_and_removed_statements() {
+    let diff = diff_with(|x| {
+        x.add_sort("obj", Sort_(0));
+        x.add_theorem(
+            "thm4",
+            &[],
+            &[u(Unify::Term, 0)],
+            &[p(Proof::Thm, 0)],
+            false,
+        );
+    });
+
+    assert_eq!(diff.sorts, [difference("obj", Change::Added, true)]);
+    assert_eq!(diff.theorems, [difference("thm4", Change::Added, true)]);
+    assert!(!diff.sorts[0].is_breaking());
+    assert!(!diff.theorems[0].is_breaking());
+
+    let diff = diff_with(|x| {
+        x.theorems.pop();
+        x.statements.pop();
+    });
+
+    assert_eq!(diff.theorems, [difference("thm3", Change::Removed, true)]);
+    assert!(diff.theorems[0].is_breaking());
+}
+
+#[test]
+fn theorem_with_the_same_statement_is_renamed() {
+    let diff = diff_with(|x| x.theorems[3].name = "thm3b".to_string());
+
+    assert_eq!(
+        diff.theorems,
+        [difference(
+            "thm3",
+            Change::Renamed("thm3b".to_string()),
+            true
+        )]
+    );
+    assert!(diff.theorems[0].is_breaking());
+}
+
+#[test]
+fn sort_and_term_changes() {
+    let diff = diff_with(|x| x.sorts[0].1 = Sort_(5));
+
+    assert_eq!(diff.sorts, [difference("wff", Change::Modifiers, true)]);
+    assert!(diff.sorts[0].is_breaking());
+
+    // `tt` is local, so changing it does not break anything
+    let diff = diff_with(|x| x.terms[2].unify = vec![u(Unify::Term, 0)]);
+
+    assert_eq!(diff.terms, [difference("tt", Change::Value, false)]);
+    assert!(!diff.terms[0].is_breaking());
+}
+
+#[test]
+fn term_signature_change_is_breaking() {
+    let wff = Var_::new(0, 0, false);
+    let old = {
+        let mut x = logic(&thm3_proof());
+        x.add_term("neg", &[wff], wff);
+        Verifier::new(&x.to_bytes()).unwrap()
+    };
+    let new = {
+        let mut x = logic(&thm3_proof());
+        x.add_term("neg", &[wff, wff], wff);
+        Verifier::new(&x.to_bytes()).unwrap()
+    };
+    let diff = diff(&old, &new).unwrap();
+
+    assert_eq!(diff.terms, [difference("neg", Change::Signature, true)]);
+    assert!(diff.terms[0].is_breaking());
+}
+
+#[test]
+fn theorem_changes() {
+    let diff = diff_with(|x| x.theorems[3].unify = vec![u(Unify::Term, 0)]);
+
+    assert_eq!(diff.theorems, [difference("thm3", Change::Statement, true)]);
+    assert!(diff.theorems[0].is_breaking());
+
+    let diff = diff_with(|x| {
+        let proof = x.statements[THM3].proof.as_mut().unwrap();
+        proof[12] = p(Proof::Ref, 0);
+    });
+
+    assert_eq!(diff.theorems, [difference("thm3", Change::Proof, true)]);
+    assert!(!diff.theorems[0].is_breaking());
+}
+
+#[test]
+fn theorem_that_becomes_an_axiom_is_breaking() {
+    let diff = diff_with(|x| x.statements[THM3].code = opcode::Statement::Axiom);
+    let change = Change::Kind { axiom: true };
+
+    assert_eq!(diff.theorems, [difference("thm3", change, true)]);
+    assert!(diff.theorems[0].is_breaking());
+    assert_eq!(diff.theorems[0].to_string(), "~ thm3: became an axiom");
+
+    // proving an axiom is compatible
+    let diff = diff_with(|x| x.statements[4].code = opcode::Statement::Thm);
+    let change = Change::Kind { axiom: false };
+
+    assert_eq!(diff.theorems, [difference("ax_t", change, true)]);
+    assert!(!diff.theorems[0].is_breaking());
+}
+
+#[test]
+fn public_theorem_that_becomes_local_is_breaking() {
+    let diff = diff_with(|x| x.statements[THM3].code = opcode::Statement::LocalTerm);
+
+    assert_eq!(
+        diff.theorems,
+        [difference("thm3", Change::Visibility, true)]
+    );
+    assert!(diff.theorems[0].is_breaking());
+
+    let diff = diff_with(|x| x.statements[3].code = opcode::Statement::TermDef);
+
+    assert_eq!(diff.terms, [difference("tt", Change::Visibility, false)]);
+    assert!(!diff.terms[0].is_breaking());
+}
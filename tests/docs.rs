@@ -0,0 +1,137 @@
+mod common;
+
+use common::{logic, thm3_proof};
+use trivial_verifier::docs::{self, Page};
+use trivial_verifier::mm0::Mm0;
+use trivial_verifier::Verifier;
+
+const SPEC: &str = "delimiter $ ( ) $;
+provable sort wff;
+term t: wff;
+term imp (a b: wff): wff;
+infixr imp: $->$ prec 25;
+axiom ax_t: $ t $;
+axiom ax1 (a b: wff): $ a -> b -> a $;
+axiom mp (a b: wff): $ a $ > $ a -> b $ > $ b $;
+theorem thm3: $ t -> t $;
+";
+
+fn generate(spec: Option<&str>) -> Vec<Page> {
+    let verifier = Verifier::new(&logic(&thm3_proof()).to_bytes()).unwrap();
+    let spec = spec.map(|x| Mm0::parse(x).unwrap());
+
+    docs::generate(&verifier, spec.as_ref()).unwrap()
+}
+
+fn content<'a>(pages: &'a [Page], path: &str) -> &'a str {
+    &pages.iter().find(|x| x.path == path).unwrap().content
+}
+
+/// Returns the items of the list below the heading `title`.
+fn section<'a>(page: &'a str, title: &str) -> Vec<&'a str> {
+    let start = page.find(&format!("<h2>{}</h2>\n", title)).unwrap();
+
+    page[start..]
+        .lines()
+        .skip(1)
+        .take_while(|x| !x.starts_with("<h2>") && !x.starts_with("</ul>"))
+        .filter_map(|x| x.strip_prefix("<li>")?.strip_suffix("</li>"))
+        .collect()
+}
+
+#[test]
+fn every_declaration_has_a_page() {
+    let pages = generate(None);
+    let paths: Vec<_> = pages.iter().map(|x| x.path.as_str()).collect();
+
+    assert_eq!(
+        paths,
+        [
+            "index.html",
+            "style.css",
+            "sort-0.html",
+            "term-0.html",
+            "term-1.html",
+            "term-2.html",
+            "thm-0.html",
+            "thm-1.html",
+            "thm-2.html",
+            "thm-3.html",
+        ]
+    );
+
+    assert_eq!(
+        section(content(&pages, "index.html"), "Theorems"),
+        [
+            "<a href=\"thm-0.html\">ax_t</a>",
+            "<a href=\"thm-1.html\">ax1</a>",
+            "<a href=\"thm-2.html\">mp</a>",
+            "<a href=\"thm-3.html\">thm3</a>",
+        ]
+    );
+}
+
+#[test]
+fn theorems_link_their_axioms_and_uses() {
+    let pages = generate(None);
+    let thm3 = content(&pages, "thm-3.html");
+    let links = [
+        "<a href=\"thm-0.html\">ax_t</a>",
+        "<a href=\"thm-1.html\">ax1</a>",
+        "<a href=\"thm-2.html\">mp</a>",
+    ];
+
+    assert_eq!(section(thm3, "Axioms"), links);
+    assert_eq!(section(thm3, "Uses"), links);
+    assert!(section(thm3, "Used by").is_empty());
+
+    // axioms do not list what they use
+    let ax_t = content(&pages, "thm-0.html");
+
+    assert!(ax_t.starts_with("<!DOCTYPE html>"));
+    assert!(ax_t.contains("<h1>axiom ax_t</h1>"));
+    assert!(!ax_t.contains("<h2>Axioms</h2>"));
+    assert_eq!(
+        section(ax_t, "Used by"),
+        ["<a href=\"thm-3.html\">thm3</a>"]
+    );
+
+    assert_eq!(
+        section(content(&pages, "term-0.html"), "Mentioned by"),
+        [
+            "<a href=\"thm-0.html\">ax_t</a>",
+            "<a href=\"thm-3.html\">thm3</a>",
+        ]
+    );
+}
+
+#[test]
+fn formulas_are_escaped() {
+    let plain = generate(None);
+    let noted = generate(Some(SPEC));
+
+    assert!(content(&plain, "thm-3.html").contains("<p><code>imp t t</code></p>"));
+    assert!(content(&noted, "thm-3.html").contains("<p><code>t -&gt; t</code></p>"));
+    assert_eq!(
+        section(content(&noted, "thm-2.html"), "Hypotheses"),
+        ["<code>a</code>", "<code>a -&gt; b</code>"]
+    );
+    assert!(
+        content(&noted, "term-2.html").contains("<h2>Value</h2>\n<p><code>t -&gt; t</code></p>")
+    );
+}
+
+#[test]
+fn pages_are_written_to_a_directory() {
+    let pages = generate(None);
+    let dir = std::env::temp_dir().join(format!("trivial-docs-{}", std::process::id()));
+
+    docs::write(&dir, &pages).unwrap();
+
+    for i in &pages {
+        assert_eq!(
+            std::fs::read_to_string(dir.join(&i.path)).unwrap(),
+            i.content
+        );
+    }
+}
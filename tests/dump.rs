@@ -0,0 +1,83 @@
+mod common;
+
+use common::{logic, thm3_proof, without_index};
+use serde_json::json;
+use trivial_verifier::dump::{dump, FORMAT, VERSION};
+use trivial_verifier::Verifier;
+
+fn verifier(data: &[u8]) -> Verifier {
+    let mut verifier = Verifier::new(data).unwrap();
+    verifier.run(&mut |_, _| {}).unwrap();
+    verifier
+}
+
+#[test]
+fn dump_lists_every_statement() {
+    let verifier = verifier(&logic(&thm3_proof()).to_bytes());
+    let dump = dump(&verifier).unwrap();
+
+    assert_eq!(dump["format"], FORMAT);
+    assert_eq!(dump["version"], VERSION);
+    assert_eq!(dump["sorts"].as_array().unwrap().len(), 1);
+    assert_eq!(dump["terms"].as_array().unwrap().len(), 3);
+    assert_eq!(dump["theorems"].as_array().unwrap().len(), 4);
+
+    assert_eq!(
+        dump["sorts"][0],
+        json!({
+            "index": 0,
+            "name": "wff",
+            "pure": false,
+            "strict": false,
+            "provable": true,
+            "free": false,
+        })
+    );
+
+    let wff = json!({ "sort": 0, "bound": false, "deps": [] });
+    let t = json!({ "term": 0, "args": [] });
+
+    assert_eq!(
+        dump["terms"][2],
+        json!({
+            "index": 2,
+            "name": "tt",
+            "local": true,
+            "definition": true,
+            "binders": [],
+            "return": wff,
+            "value": { "term": 1, "args": [t, t] },
+            "dummies": [],
+        })
+    );
+
+    assert_eq!(
+        dump["theorems"][2],
+        json!({
+            "index": 2,
+            "name": "mp",
+            "local": false,
+            "axiom": true,
+            "binders": [wff, wff],
+            "hypotheses": [
+                { "var": 0 },
+                { "term": 1, "args": [{ "var": 0 }, { "var": 1 }] },
+            ],
+            "conclusion": { "var": 1 },
+        })
+    );
+
+    assert_eq!(dump["terms"][0]["value"], json!(null));
+    assert_eq!(dump["theorems"][3]["axiom"], false);
+}
+
+#[test]
+fn names_are_null_without_an_index() {
+    let verifier = verifier(&without_index(logic(&thm3_proof()).to_bytes()));
+    let dump = dump(&verifier).unwrap();
+
+    assert_eq!(dump["sorts"][0]["name"], json!(null));
+    assert_eq!(dump["terms"][1]["name"], json!(null));
+    assert_eq!(dump["theorems"][3]["name"], json!(null));
+    assert_eq!(dump["theorems"][3]["conclusion"]["term"], 1);
+}
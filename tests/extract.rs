@@ -0,0 +1,88 @@
+mod common;
+
+use common::{logic, p, thm3_proof};
+use trivial_verifier::expr::Expr;
+use trivial_verifier::extract::extract;
+use trivial_verifier::kernel::opcode::Proof;
+use trivial_verifier::Verifier;
+
+fn verifier() -> Verifier {
+    Verifier::new(&logic(&thm3_proof()).to_bytes()).unwrap()
+}
+
+fn names(verifier: &Verifier) -> (Vec<&str>, Vec<&str>, Vec<&str>) {
+    let sorts = (0..verifier.table.sorts.len())
+        .map(|x| verifier.get_sort_name(x).unwrap())
+        .collect();
+    let terms = (0..verifier.table.terms.len())
+        .map(|x| verifier.get_term_name(x).unwrap())
+        .collect();
+    let theorems = (0..verifier.table.theorems.len())
+        .map(|x| verifier.get_theorem_name(x).unwrap())
+        .collect();
+
+    (sorts, terms, theorems)
+}
+
+#[test]
+fn theorem_is_extracted_with_its_dependencies() {
+    let verifier = verifier();
+    let data = extract(&verifier, 3).unwrap().to_bytes();
+
+    let mut extracted = Verifier::new(&data).unwrap();
+    extracted.run(&mut |_, _| {}).unwrap();
+
+    // the definition `tt` is not used by `thm3`
+    assert_eq!(
+        names(&extracted),
+        (
+            vec!["wff"],
+            vec!["t", "imp"],
+            vec!["ax_t", "ax1", "mp", "thm3"]
+        )
+    );
+    assert_eq!(
+        extracted.get_theorem_statement(3).unwrap(),
+        verifier.get_theorem_statement(3).unwrap()
+    );
+}
+
+#[test]
+fn terms_and_theorems_are_renumbered() {
+    let data = extract(&verifier(), 1).unwrap().to_bytes();
+
+    let mut extracted = Verifier::new(&data).unwrap();
+    extracted.run(&mut |_, _| {}).unwrap();
+
+    assert_eq!(names(&extracted), (vec!["wff"], vec!["imp"], vec!["ax1"]));
+
+    let (_, conclusion) = extracted.get_theorem_statement(0).unwrap();
+    assert_eq!(
+        conclusion,
+        Expr::Term(
+            0,
+            vec![
+                Expr::Var(0),
+                Expr::Term(0, vec![Expr::Var(1), Expr::Var(0)])
+            ]
+        )
+    );
+}
+
+#[test]
+fn missing_theorem_is_not_extracted() {
+    assert!(extract(&verifier(), 4).is_none());
+}
+
+#[test]
+fn dangling_operands_are_not_extracted() {
+    for last in [p(Proof::Thm, 9), p(Proof::Term, 9)] {
+        let mut proof = thm3_proof();
+        *proof.last_mut().unwrap() = last;
+
+        let verifier = Verifier::new(&logic(&proof).to_bytes()).unwrap();
+
+        assert!(extract(&verifier, 3).is_none());
+        assert!(extract(&verifier, 2).is_some());
+    }
+}
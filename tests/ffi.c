@@ -0,0 +1,103 @@
+/* Exercises the C interface, called by tests/ffi.rs with a valid file, a
+ * file whose proof leaves two expressions on the stack and the valid file
+ * without its index. */
+
+#include "trivial_verifier.h"
+
+#include <stdio.h>
+#include <string.h>
+
+#define CHECK(x)                                                       \
+    do {                                                               \
+        if (!(x)) {                                                    \
+            fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__,     \
+                    __LINE__, #x);                                     \
+            return 1;                                                  \
+        }                                                              \
+    } while (0)
+
+static size_t read_file(const char *path, uint8_t *buf, size_t len) {
+    FILE *file = fopen(path, "rb");
+    size_t n;
+
+    if (!file) {
+        return 0;
+    }
+
+    n = fread(buf, 1, len, file);
+    fclose(file);
+
+    return n;
+}
+
+int main(int argc, char **argv) {
+    static uint8_t data[1 << 16];
+    TrivialVerifier *verifier;
+    TrivialStatus status;
+    size_t len, sorts, terms, theorems;
+    char name[8];
+
+    CHECK(argc == 4);
+
+    CHECK(trivial_verifier_new(data, 8) == NULL);
+    CHECK(trivial_verifier_run(NULL) == TRIVIAL_STATUS_INVALID_ARGUMENT);
+
+    len = read_file(argv[1], data, sizeof(data));
+    CHECK(len > 0);
+
+    verifier = trivial_verifier_new(data, len);
+    CHECK(verifier != NULL);
+
+    CHECK(trivial_verifier_counts(verifier, &sorts, &terms, &theorems) ==
+          TRIVIAL_STATUS_OK);
+    CHECK(sorts == 1 && terms == 3 && theorems == 4);
+
+    CHECK(trivial_verifier_sort_name(verifier, 0, name, sizeof(name)) == 3);
+    CHECK(strcmp(name, "wff") == 0);
+    CHECK(trivial_verifier_term_name(verifier, 0, NULL, 0) == 1);
+    CHECK(trivial_verifier_theorem_name(verifier, 4, name, sizeof(name)) == -1);
+
+    /* names are truncated to the buffer, but their whole length is returned */
+    CHECK(trivial_verifier_theorem_name(verifier, 3, name, 3) == 4);
+    CHECK(strcmp(name, "th") == 0);
+
+    CHECK(trivial_verifier_sort_index(verifier, "wff") == 0);
+    CHECK(trivial_verifier_term_index(verifier, "t") == 0);
+    CHECK(trivial_verifier_theorem_index(verifier, "thm3") == 3);
+    CHECK(trivial_verifier_theorem_index(verifier, "none") == -1);
+
+    CHECK(trivial_verifier_step(verifier) == TRIVIAL_STATUS_OK);
+
+    do {
+        status = trivial_verifier_step(verifier);
+    } while (status == TRIVIAL_STATUS_OK);
+
+    CHECK(status == TRIVIAL_STATUS_FINISHED);
+    trivial_verifier_free(verifier);
+
+    len = read_file(argv[2], data, sizeof(data));
+    CHECK(len > 0);
+
+    verifier = trivial_verifier_new(data, len);
+    CHECK(verifier != NULL);
+
+    status = trivial_verifier_run(verifier);
+    CHECK(status == TRIVIAL_STATUS_STACK_HAS_MORE_THAN_ONE);
+    CHECK(strcmp(trivial_status_message(status), "StackHasMoreThanOne") == 0);
+
+    trivial_verifier_free(verifier);
+
+    len = read_file(argv[3], data, sizeof(data));
+    CHECK(len > 0);
+
+    verifier = trivial_verifier_new(data, len);
+    CHECK(verifier != NULL);
+
+    CHECK(trivial_verifier_sort_name(verifier, 0, name, sizeof(name)) == -1);
+    CHECK(trivial_verifier_theorem_index(verifier, "thm3") == -1);
+    CHECK(trivial_verifier_run(verifier) == TRIVIAL_STATUS_OK);
+
+    trivial_verifier_free(verifier);
+
+    return 0;
+}
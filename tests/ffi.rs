@@ -0,0 +1,65 @@
+//! Compiles and runs `tests/ffi.c` against the shared library, run with
+//! `cargo test --features ffi`.
+
+#![cfg(feature = "ffi")]
+
+mod common;
+
+use common::{logic, thm3_proof, unfinished_thm3_proof, without_index};
+use std::path::PathBuf;
+use std::process::Command as Process;
+
+#[test]
+fn c_program() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    // the test binary is next to the library of the same build, which is
+    // only copied to the target directory by `cargo build`
+    let exe = std::env::current_exe().unwrap();
+    let lib = exe.parent().unwrap();
+
+    let dir = std::env::temp_dir().join(format!("trivial-ffi-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let good = dir.join("good.mmb");
+    let bad = dir.join("bad.mmb");
+    let plain = dir.join("plain.mmb");
+    let program = dir.join("ffi");
+
+    let data = logic(&thm3_proof()).to_bytes();
+
+    std::fs::write(&good, &data).unwrap();
+    std::fs::write(&bad, logic(&unfinished_thm3_proof()).to_bytes()).unwrap();
+    std::fs::write(&plain, without_index(data)).unwrap();
+
+    let status = Process::new("cc")
+        .arg(root.join("tests/ffi.c"))
+        .arg("-I")
+        .arg(root.join("include"))
+        .arg("-L")
+        .arg(lib)
+        .arg(format!("-Wl,-rpath,{}", lib.display()))
+        .arg("-ltrivial_verifier")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-o")
+        .arg(&program)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+
+    // cargo puts the target directory first in the library path, which
+    // takes precedence over the rpath
+    let status = Process::new(&program)
+        .arg(&good)
+        .arg(&bad)
+        .arg(&plain)
+        .env("LD_LIBRARY_PATH", lib)
+        .status()
+        .unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.success());
+}
@@ -0,0 +1,137 @@
+mod common;
+
+use common::{failing_logic, logic, thm3_proof};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use trivial_verifier::lsp::LanguageServer;
+use trivial_verifier::server::Handler;
+
+const SPEC: &str = "delimiter $ ( ) $;
+provable sort wff;
+term t: wff;
+term imp (a b: wff): wff;
+infixr imp: $->$ prec 25;
+axiom ax_t: $ t $;
+axiom ax1 (a b: wff): $ a -> b -> a $;
+axiom mp (a b: wff): $ a $ > $ a -> b $ > $ b $;
+theorem thm3: $ t -> t $;
+";
+
+/// Writes `name.mm0` with `text` and `name.mmb` with `data`, and returns the
+/// uri of the specification.
+fn write(name: &str, text: &str, data: &[u8]) -> String {
+    let dir = std::env::temp_dir().join(format!("trivial-lsp-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let path: PathBuf = dir.join(name);
+    std::fs::write(path.with_extension("mm0"), text).unwrap();
+    std::fs::write(path.with_extension("mmb"), data).unwrap();
+
+    format!("file://{}", path.with_extension("mm0").display())
+}
+
+/// Opens a document and returns the diagnostics that are published for it.
+fn open(server: &mut LanguageServer, uri: &str, text: &str) -> Vec<Value> {
+    let message = json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "text": text } },
+    });
+
+    let mut notifications = Vec::new();
+    assert_eq!(
+        server.handle(&message, &mut |x| notifications.push(x)),
+        None
+    );
+
+    let last = notifications.pop().unwrap();
+    assert_eq!(last["method"], "textDocument/publishDiagnostics");
+    assert_eq!(last["params"]["uri"], uri);
+
+    last["params"]["diagnostics"].as_array().unwrap().clone()
+}
+
+fn hover(server: &mut LanguageServer, uri: &str, line: u64) -> Value {
+    let message = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "textDocument/hover",
+        "params": {
+            "textDocument": { "uri": uri },
+            "position": { "line": line, "character": 10 },
+        },
+    });
+
+    server.handle(&message, &mut |_| {}).unwrap()["result"].clone()
+}
+
+#[test]
+fn valid_proofs_have_no_diagnostics() {
+    let uri = write("good", SPEC, &logic(&thm3_proof()).to_bytes());
+    let mut server = LanguageServer::new();
+
+    assert_eq!(open(&mut server, &uri, SPEC), Vec::<Value>::new());
+
+    let value = hover(&mut server, &uri, 8);
+    let value = value["contents"]["value"].as_str().unwrap();
+
+    assert!(value.starts_with("```\ntheorem thm3\n"), "{}", value);
+    assert!(value.ends_with("axioms: `ax_t`, `ax1`, `mp`"), "{}", value);
+}
+
+#[test]
+fn failures_are_reported_on_the_theorems_that_use_them() {
+    let text = format!(
+        "{}theorem thm4: $ t -> t $;\ntheorem thm5: $ t -> t $;\n",
+        SPEC
+    );
+    let uri = write("bad", &text, &failing_logic().to_bytes());
+    let mut server = LanguageServer::new();
+
+    let diagnostics: Vec<_> = open(&mut server, &uri, &text)
+        .iter()
+        .map(|x| {
+            let line = x["range"]["start"]["line"].as_u64().unwrap();
+            let severity = x["severity"].as_u64().unwrap();
+            (line, severity, x["message"].as_str().unwrap().to_string())
+        })
+        .collect();
+
+    assert_eq!(
+        diagnostics,
+        [
+            (8, 1, "fails: StackHasMoreThanOne".to_string()),
+            (
+                9,
+                2,
+                "uses thm3, which fails: StackHasMoreThanOne".to_string()
+            ),
+            (10, 1, "fails: StackHasMoreThanOne".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn missing_proofs_are_a_warning() {
+    let uri = "file:///nonexistent/spec.mm0";
+    let mut server = LanguageServer::new();
+
+    let diagnostics = open(&mut server, uri, SPEC);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 2);
+    assert_eq!(hover(&mut server, uri, 8), Value::Null);
+}
+
+#[test]
+fn parse_errors_are_reported_where_they_occur() {
+    let uri = write("parse", SPEC, &logic(&thm3_proof()).to_bytes());
+    let text = SPEC.replace("theorem thm3: $ t -> t $;", "theorem thm3: $ t -> t ;");
+    let mut server = LanguageServer::new();
+
+    let diagnostics = open(&mut server, &uri, &text);
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 8);
+}
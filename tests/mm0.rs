@@ -0,0 +1,312 @@
+mod common;
+
+use common::{logic, p, thm3_proof, u, without_index};
+use trivial_verifier::expr::Expr;
+use trivial_verifier::kernel::opcode::{Proof, Unify};
+use trivial_verifier::kernel::{Sort_, Var, Var_};
+use trivial_verifier::mm0::{
+    self, check, Env, MathError, Mismatch, MismatchKind, Mm0, ParseErrorKind, Printer, Statement,
+};
+use trivial_verifier::Verifier;
+
+const SPEC: &str = "delimiter $ ( ) $;
+provable sort wff;
+term t: wff;
+term imp (a b: wff): wff;
+infixr imp: $->$ prec 25;
+axiom ax_t: $ t $;
+axiom ax1 (a b: wff): $ a -> b -> a $;
+axiom mp (a b: wff): $ a $ > $ a -> b $ > $ b $;
+theorem thm3: $ t -> t $;
+";
+
+fn verifier() -> Verifier {
+    let mut verifier = Verifier::new(&logic(&thm3_proof()).to_bytes()).unwrap();
+    verifier.run(&mut |_, _| {}).unwrap();
+    verifier
+}
+
+/// Checks the fixture against `SPEC` with `from` replaced by `to`.
+fn check_edited(from: &str, to: &str) -> Result<(), Mismatch> {
+    assert!(SPEC.contains(from));
+
+    let spec = Mm0::parse(&SPEC.replacen(from, to, 1)).unwrap();
+
+    check(&verifier(), &spec)
+}
+
+fn kind(result: Result<(), Mismatch>) -> (String, MismatchKind) {
+    let mismatch = result.unwrap_err();
+
+    (mismatch.name, mismatch.kind)
+}
+
+#[test]
+fn parses_statements_and_notations() {
+    let spec = Mm0::parse(SPEC).unwrap();
+    let names: Vec<_> = spec.statements.iter().map(Statement::name).collect();
+
+    assert_eq!(names, ["wff", "t", "imp", "ax_t", "ax1", "mp", "thm3"]);
+    assert_eq!(spec.delimiters, ['(', ')']);
+    assert_eq!(spec.notations.len(), 1);
+    assert_eq!(spec.notations[0].token, "->");
+
+    match spec.get_statement("mp") {
+        Some(Statement::Assert(mp)) => {
+            assert!(mp.axiom);
+            assert_eq!(mp.binders.len(), 2);
+            assert_eq!(mp.hyps.len(), 2);
+            assert_eq!(mp.conclusion.text.trim(), "b");
+            assert_eq!(&SPEC[mp.offset..(mp.offset + 8)], "axiom mp");
+        }
+        x => panic!("{:?}", x),
+    }
+}
+
+#[test]
+fn parse_errors_have_offsets() {
+    let error = Mm0::parse("sort wff;\nterm t: wff").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnexpectedEnd);
+
+    let error = Mm0::parse("sort wff;\naxiom a: $ t ;").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::UnterminatedMath);
+    assert_eq!(
+        mm0::position("sort wff;\naxiom a: $ t ;", error.offset),
+        (1, 9)
+    );
+
+    let error = Mm0::parse("sort wff;\naxiom a (x: wff): wff > wff;").unwrap_err();
+    assert_eq!(error.kind, ParseErrorKind::MissingConclusion);
+}
+
+#[test]
+fn formulas_follow_precedence_and_associativity() {
+    let spec = Mm0::parse(SPEC).unwrap();
+    let env = Env::new(&spec).unwrap();
+    let t = || Expr::Term(0, vec![]);
+
+    assert_eq!(
+        env.parse_statement("t -> t -> t", &[]),
+        Ok(Expr::Term(1, vec![t(), Expr::Term(1, vec![t(), t()])]))
+    );
+    assert_eq!(
+        env.parse_statement("(t -> t) -> t", &[]),
+        Ok(Expr::Term(1, vec![Expr::Term(1, vec![t(), t()]), t()]))
+    );
+    assert_eq!(
+        env.parse_statement("t -> x", &[]),
+        Err(MathError::UnexpectedToken("x".to_string()))
+    );
+    assert_eq!(
+        env.parse_statement("t ->", &[]),
+        Err(MathError::UnexpectedEnd)
+    );
+}
+
+#[test]
+fn file_matches_its_specification() {
+    assert_eq!(check(&verifier(), &Mm0::parse(SPEC).unwrap()), Ok(()));
+}
+
+#[test]
+fn mismatches_are_reported() {
+    let extra = format!("{}theorem thm4: $ t $;\n", SPEC);
+    let spec = Mm0::parse(&extra).unwrap();
+    let mismatch = check(&verifier(), &spec).unwrap_err();
+
+    assert_eq!(mismatch.kind, MismatchKind::Missing);
+    assert_eq!(mismatch.name, "thm4");
+    assert_eq!(mismatch.offset, Some(SPEC.len()));
+
+    let spec = Mm0::parse(SPEC.replace("theorem thm3: $ t -> t $;\n", "").as_str()).unwrap();
+    let mismatch = check(&verifier(), &spec).unwrap_err();
+
+    assert_eq!(mismatch.kind, MismatchKind::Unexpected);
+    assert_eq!(mismatch.name, "thm3");
+    assert_eq!(mismatch.offset, None);
+
+    assert_eq!(
+        kind(check_edited("theorem thm3", "theorem thm4")),
+        ("thm4".to_string(), MismatchKind::Name("thm3".to_string()))
+    );
+    assert_eq!(
+        kind(check_edited("theorem thm3", "axiom thm3")),
+        ("thm3".to_string(), MismatchKind::Kind)
+    );
+    assert_eq!(
+        kind(check_edited("provable sort", "sort")),
+        ("wff".to_string(), MismatchKind::SortModifiers)
+    );
+    assert_eq!(
+        kind(check_edited("term imp (a b: wff)", "term imp (a: wff)")),
+        ("imp".to_string(), MismatchKind::Binders)
+    );
+    assert_eq!(
+        kind(check_edited("term t: wff", "def t: wff")),
+        ("t".to_string(), MismatchKind::Definition)
+    );
+    assert_eq!(
+        kind(check_edited("$ a $ > $ a -> b $", "$ a -> b $ > $ a $")),
+        ("mp".to_string(), MismatchKind::Hypotheses)
+    );
+    assert_eq!(
+        kind(check_edited("$ t -> t $", "$ t $")),
+        ("thm3".to_string(), MismatchKind::Conclusion)
+    );
+    assert_eq!(
+        kind(check_edited("$ a -> b -> a $", "$ a -> c $")),
+        (
+            "ax1".to_string(),
+            MismatchKind::Formula(MathError::UnexpectedToken("c".to_string()))
+        )
+    );
+}
+
+#[test]
+fn repeated_dependencies_are_counted_once() {
+    let wff = Var_::new(0, 0, false);
+    let mut builder = logic(&thm3_proof());
+
+    builder.add_sort("obj", Sort_(0));
+    builder.add_term("all", &[Var_::new(1, 1, true), Var_::new(0, 1, false)], wff);
+
+    let mut verifier = Verifier::new(&builder.to_bytes()).unwrap();
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    let spec = format!(
+        "{}sort obj;\nterm all {{x: obj}} (p: wff x x): wff;\n",
+        SPEC
+    );
+    assert_eq!(check(&verifier, &Mm0::parse(&spec).unwrap()), Ok(()));
+
+    let spec = format!("{}sort obj;\nterm all {{x: obj}} (p: wff): wff;\n", SPEC);
+    assert_eq!(
+        check(&verifier, &Mm0::parse(&spec).unwrap()).map_err(|x| x.kind),
+        Err(MismatchKind::Binders)
+    );
+}
+
+fn print(spec: &str, expr: &Expr) -> String {
+    let verifier = verifier();
+    let spec = Mm0::parse(spec).unwrap();
+    let printer = Printer::new(&verifier, &spec).unwrap();
+
+    printer.to_display(expr, &[]).to_string()
+}
+
+fn imp(a: Expr, b: Expr) -> Expr {
+    Expr::Term(1, vec![a, b])
+}
+
+fn t() -> Expr {
+    Expr::Term(0, vec![])
+}
+
+#[test]
+fn printer_uses_notations_and_parentheses() {
+    let nested = imp(imp(t(), t()), imp(t(), t()));
+
+    assert_eq!(print(SPEC, &nested), "(t -> t) -> t -> t");
+    assert_eq!(print("", &nested), "imp (imp t t) (imp t t)");
+
+    let prefix = format!("{}prefix t: $T$ prec max;\n", SPEC);
+    assert_eq!(print(&prefix, &nested), "(T -> T) -> T -> T");
+
+    let verifier = verifier();
+    let spec = Mm0::parse(SPEC).unwrap();
+    let printer = Printer::new(&verifier, &spec).unwrap();
+
+    assert_eq!(
+        printer.display_theorem(&verifier, 2).unwrap(),
+        (vec!["a".to_string(), "a -> b".to_string()], "b".to_string())
+    );
+}
+
+#[test]
+fn printed_formulas_parse_back() {
+    let prefix = format!("{}prefix t: $T$ prec max;\n", SPEC);
+    let general = SPEC.replace(
+        "infixr imp: $->$ prec 25;",
+        "notation imp (a b: wff): wff = ($[$:max) a ($->$:25) b ($]$:0);",
+    );
+    let exprs = [
+        t(),
+        imp(t(), t()),
+        imp(imp(t(), t()), t()),
+        imp(t(), imp(t(), imp(t(), t()))),
+        imp(imp(t(), imp(t(), t())), imp(imp(t(), t()), t())),
+    ];
+
+    // an empty specification has no terms to parse its plain applications
+    let specs = [
+        ("", SPEC),
+        (SPEC, SPEC),
+        (&prefix, &prefix),
+        (&general, &general),
+    ];
+
+    for (printed, parsed) in specs.iter() {
+        let parsed = Mm0::parse(parsed).unwrap();
+        let env = Env::new(&parsed).unwrap();
+
+        for expr in &exprs {
+            let text = print(printed, expr);
+
+            assert_eq!(
+                env.parse_statement(&text, &[]).as_ref(),
+                Ok(expr),
+                "{}",
+                text
+            );
+        }
+    }
+}
+
+/// The fixture with an exported definition and a binder with dependencies.
+fn extended() -> Vec<u8> {
+    let wff = Var_::new(0, 0, false);
+    let mut builder = logic(&thm3_proof());
+
+    builder.add_def(
+        "tt2",
+        &[],
+        wff,
+        &[u(Unify::Term, 1), u(Unify::Term, 0), u(Unify::Term, 0)],
+        &[p(Proof::Term, 0), p(Proof::Term, 0), p(Proof::Term, 1)],
+        false,
+    );
+    builder.add_sort("obj", Sort_(0));
+    builder.add_term("all", &[Var_::new(1, 1, true), Var_::new(0, 1, false)], wff);
+
+    builder.to_bytes()
+}
+
+#[test]
+fn exported_specification_checks() {
+    for data in [extended(), without_index(extended())] {
+        let mut verifier = Verifier::new(&data).unwrap();
+        verifier.run(&mut |_, _| {}).unwrap();
+
+        let text = mm0::export(&verifier).unwrap();
+        let spec = Mm0::parse(&text).unwrap_or_else(|e| panic!("{:?}\n{}", e, text));
+
+        assert_eq!(check(&verifier, &spec), Ok(()), "{}", text);
+    }
+}
+
+#[test]
+fn export_names_variables_by_position() {
+    let mut verifier = Verifier::new(&extended()).unwrap();
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    let text = mm0::export(&verifier).unwrap();
+    let lines: Vec<_> = text.lines().collect();
+
+    assert_eq!(lines[0], "delimiter $ ( ) $;");
+    assert_eq!(lines[1], "provable sort wff;");
+    assert!(lines.contains(&"term imp (v0: wff) (v1: wff): wff;"));
+    assert!(lines.contains(&"def tt2: wff = $ imp t t $;"));
+    assert!(lines.contains(&"term all {v0: obj} (v1: wff v0): wff;"));
+    assert!(text
+        .contains("axiom mp (v0: wff) (v1: wff):\n    $ v0 $ >\n    $ imp v0 v1 $ >\n    $ v1 $;"));
+}
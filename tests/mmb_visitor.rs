@@ -0,0 +1,77 @@
+mod common;
+
+use common::{logic, thm3_proof};
+use mmb_parser::Mmb;
+use trivial_verifier::mmb_visitor::{Capacity, MmbVisitor};
+use trivial_verifier::{OneShotVerifier, Verifier, ZeroCopyVerifier};
+
+#[test]
+fn capacity_matches_the_file() {
+    let builder = logic(&thm3_proof());
+    let data = builder.to_bytes();
+    let mmb = Mmb::from(&data).unwrap();
+    let capacity = Capacity::from_mmb(&mmb);
+
+    assert_eq!(capacity.sorts, builder.sorts.len());
+    assert_eq!(capacity.terms, builder.terms.len());
+    assert_eq!(capacity.theorems, builder.theorems.len());
+
+    // the return types of terms are stored with their binders, and every
+    // stream ends with an end command
+    let terms = &builder.terms;
+    let theorems = &builder.theorems;
+
+    let binders = terms.iter().map(|x| x.binders.len() + 1).sum::<usize>()
+        + theorems.iter().map(|x| x.binders.len()).sum::<usize>();
+    let unify = terms.iter().map(|x| x.unify.len() + 1).sum::<usize>()
+        + theorems.iter().map(|x| x.unify.len() + 1).sum::<usize>();
+    let proofs = builder
+        .statements
+        .iter()
+        .filter_map(|x| x.proof.as_ref())
+        .map(|x| x.len() + 1)
+        .sum::<usize>();
+
+    assert!(capacity.binders >= binders, "{:?}", capacity);
+    assert!(capacity.unify >= unify, "{:?}", capacity);
+    assert!(capacity.proofs >= proofs, "{:?}", capacity);
+
+    assert!(capacity.binders <= data.len());
+    assert!(capacity.unify <= data.len());
+    assert!(capacity.proofs <= data.len());
+
+    let mut visitor = MmbVisitor::with_capacity(capacity);
+    mmb.visit(&mut visitor).unwrap();
+}
+
+#[test]
+fn inflated_counts_are_rejected() {
+    let data = logic(&thm3_proof()).to_bytes();
+
+    // 2^28 terms or theorems would take 2 GiB in the tables alone
+    for offset in [8, 12] {
+        let mut data = data.clone();
+        data[offset..(offset + 4)].copy_from_slice(&0x1000_0000u32.to_le_bytes());
+
+        assert!(Mmb::from(&data).is_none());
+        assert!(Verifier::new(&data).is_none());
+        assert!(OneShotVerifier::new(&data).is_none());
+        assert!(ZeroCopyVerifier::new(&data).is_none());
+    }
+}
+
+#[test]
+fn capacity_is_bounded_by_the_tables() {
+    let data = logic(&thm3_proof()).to_bytes();
+    let mut mmb = Mmb::from(&data).unwrap();
+
+    mmb.num_sorts = u8::MAX;
+    mmb.num_terms = u32::MAX;
+    mmb.num_theorems = u32::MAX;
+
+    let capacity = Capacity::from_mmb(&mmb);
+
+    assert_eq!(capacity.sorts, 1);
+    assert_eq!(capacity.terms, 3);
+    assert_eq!(capacity.theorems, 4);
+}
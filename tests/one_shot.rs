@@ -0,0 +1,153 @@
+mod common;
+
+use common::{logic, p, thm3_proof, unfinished_thm3_proof, without_index};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use trivial_verifier::kernel::error::Kind;
+use trivial_verifier::kernel::opcode::{Command, Proof, Unify};
+use trivial_verifier::{OneShotVerifier, ZeroCopyVerifier};
+
+/// Counts the bytes that are allocated by the current thread, so that tests
+/// running in parallel do not disturb each other.
+struct Counting;
+
+thread_local! {
+    static LIVE: Cell<isize> = const { Cell::new(0) };
+}
+
+fn count(delta: isize) {
+    // the counter is gone while the thread shuts down
+    let _ = LIVE.try_with(|x| x.set(x.get() + delta));
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+
+        if !ptr.is_null() {
+            count(layout.size() as isize);
+        }
+
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        count(-(layout.size() as isize));
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn live() -> isize {
+    LIVE.with(|x| x.get())
+}
+
+const UNIFY: [Command<Unify>; 3] = [
+    Command {
+        opcode: Unify::Term,
+        operand: 1,
+    },
+    Command {
+        opcode: Unify::Term,
+        operand: 0,
+    },
+    Command {
+        opcode: Unify::Term,
+        operand: 0,
+    },
+];
+
+/// The fixture with `thm4`, which proves `t -> t` again with `proof`.
+fn with_thm4(proof: &[Command<Proof>], local: bool) -> Vec<u8> {
+    let mut builder = logic(&thm3_proof());
+    builder.add_theorem("thm4", &[], &UNIFY, proof, local);
+    builder.to_bytes()
+}
+
+fn one_shot(data: &[u8]) -> Result<(), Kind> {
+    OneShotVerifier::new(data).unwrap().run(&mut |_, _| {})
+}
+
+fn zero_copy(data: &[u8]) -> Result<(), Kind> {
+    ZeroCopyVerifier::new(data).unwrap().run(&mut |_, _| {})
+}
+
+#[test]
+fn proof_streams_are_freed_once_verified() {
+    let mut builder = logic(&thm3_proof());
+
+    for i in 0..500 {
+        builder.add_theorem(&format!("copy{}", i), &[], &UNIFY, &thm3_proof(), false);
+    }
+
+    let data = builder.to_bytes();
+    let proofs = 500 * (thm3_proof().len() + 1) * std::mem::size_of::<Command<Proof>>();
+
+    let mut verifier = OneShotVerifier::new(&data).unwrap();
+    let before = live();
+
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    // the kernel keeps a few buffers of the size of a single proof
+    assert!(
+        live() < before - proofs as isize / 2,
+        "{} {}",
+        before,
+        live()
+    );
+}
+
+#[test]
+fn failing_proofs_are_rejected() {
+    let data = logic(&unfinished_thm3_proof()).to_bytes();
+
+    assert_eq!(one_shot(&data), Err(Kind::StackHasMoreThanOne));
+    assert_eq!(zero_copy(&data), Err(Kind::StackHasMoreThanOne));
+
+    let mut proof = thm3_proof();
+    *proof.last_mut().unwrap() = p(Proof::Thm, 1);
+    let data = logic(&proof).to_bytes();
+
+    assert!(one_shot(&data).is_err());
+    assert!(zero_copy(&data).is_err());
+}
+
+#[test]
+fn local_theorems_are_checked() {
+    let data = with_thm4(&thm3_proof(), true);
+
+    assert_eq!(one_shot(&data), Ok(()));
+    assert_eq!(zero_copy(&data), Ok(()));
+
+    let data = with_thm4(&unfinished_thm3_proof(), true);
+
+    assert_eq!(one_shot(&data), Err(Kind::StackHasMoreThanOne));
+    assert_eq!(zero_copy(&data), Err(Kind::StackHasMoreThanOne));
+}
+
+#[test]
+fn truncated_proof_stream_is_rejected() {
+    let data = logic(&thm3_proof()).to_bytes();
+    let mut index = [0; 8];
+    index.copy_from_slice(&data[32..40]);
+
+    // the index follows the proofs, so this cuts off the end of the proof of
+    // `thm3`, the last statement
+    let data = without_index(data);
+    let data = &data[..(u64::from_le_bytes(index) as usize - 12)];
+
+    assert!(OneShotVerifier::new(data).is_none());
+    assert!(ZeroCopyVerifier::new(data).is_none());
+}
+
+#[test]
+fn dangling_theorem_is_an_error() {
+    let mut proof = thm3_proof();
+    *proof.last_mut().unwrap() = p(Proof::Thm, 9);
+    let data = with_thm4(&proof, false);
+
+    assert!(one_shot(&data).is_err());
+    assert!(zero_copy(&data).is_err());
+}
@@ -0,0 +1,52 @@
+# Exercises the Python bindings, called by tests/python.rs with a valid file
+# and a file with an invalid proof.
+
+import sys
+
+from trivial_verifier import Verifier
+
+good, bad = sys.argv[1:]
+
+verifier = Verifier.load(good)
+
+assert verifier.sort_name(0) == "wff"
+assert verifier.term_index("imp") == 1
+assert verifier.theorem_name(3) == "thm3"
+assert verifier.theorem_index("none") is None
+
+terms = verifier.terms()
+assert [x["name"] for x in terms] == ["t", "imp", "tt"]
+assert terms[1]["binders"] == [("wff", False, 0), ("wff", False, 0)]
+assert terms[1]["return"] == ("wff", False, 0)
+assert terms[1]["value"] is None
+assert terms[2]["value"] == ("imp", ("t",), ("t",))
+
+theorems = verifier.theorems()
+assert [x["name"] for x in theorems] == ["ax_t", "ax1", "mp", "thm3"]
+assert [x["axiom"] for x in theorems] == [True, True, True, False]
+assert theorems[1]["conclusion"] == ("imp", 0, ("imp", 1, 0))
+assert theorems[3]["conclusion"] == ("imp", ("t",), ("t",))
+assert verifier.theorem_statement(2) == ([0, ("imp", 0, 1)], 1)
+
+actions = []
+assert verifier.step(actions.append)
+
+while verifier.step():
+    pass
+
+assert actions == ["Sort"]
+
+with open(bad, "rb") as file:
+    verifier = Verifier(file.read())
+
+try:
+    verifier.run()
+    assert False
+except ValueError as e:
+    assert str(e) == "StackHasMoreThanOne"
+
+try:
+    Verifier(b"")
+    assert False
+except ValueError:
+    pass
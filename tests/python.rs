@@ -0,0 +1,47 @@
+//! Loads the shared library as a Python module and runs `tests/python.py`,
+//! run with `cargo test --features python`.
+
+#![cfg(feature = "python")]
+
+mod common;
+
+use common::{logic, thm3_proof, unfinished_thm3_proof};
+use std::path::PathBuf;
+use std::process::Command as Process;
+
+#[test]
+fn python_script() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+
+    // the test binary is next to the library of the same build, which is
+    // only copied to the target directory by `cargo build`
+    let exe = std::env::current_exe().unwrap();
+    let lib = exe.parent().unwrap();
+
+    let dir = std::env::temp_dir().join(format!("trivial-python-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let good = dir.join("good.mmb");
+    let bad = dir.join("bad.mmb");
+
+    std::fs::write(&good, logic(&thm3_proof()).to_bytes()).unwrap();
+    std::fs::write(&bad, logic(&unfinished_thm3_proof()).to_bytes()).unwrap();
+
+    std::fs::copy(
+        lib.join("libtrivial_verifier.so"),
+        dir.join("trivial_verifier.so"),
+    )
+    .unwrap();
+
+    let status = Process::new("python3")
+        .arg(root.join("tests/python.py"))
+        .arg(&good)
+        .arg(&bad)
+        .env("PYTHONPATH", &dir)
+        .status()
+        .unwrap();
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(status.success());
+}
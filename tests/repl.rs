@@ -0,0 +1,90 @@
+mod common;
+
+use common::{logic, thm3_proof};
+use trivial_verifier::repl::Repl;
+use trivial_verifier::Verifier;
+
+fn repl() -> Repl {
+    Repl::new(Verifier::new(&logic(&thm3_proof()).to_bytes()).unwrap())
+}
+
+#[test]
+fn names_are_sorted() {
+    assert_eq!(
+        repl().names(),
+        ["ax1", "ax_t", "imp", "mp", "t", "thm3", "tt", "wff"]
+    );
+}
+
+#[test]
+fn queries() {
+    let mut repl = repl();
+
+    assert_eq!(
+        repl.execute("show mp").unwrap(),
+        "  hypothesis v0\n  hypothesis (imp v0 v1)\n  conclusion v1"
+    );
+    assert_eq!(repl.execute("show 3"), repl.execute("show thm3"));
+    assert_eq!(
+        repl.execute("deps thm3").unwrap(),
+        "terms: t imp\ntheorems: ax_t ax1 mp"
+    );
+    assert_eq!(repl.execute("axioms thm3").unwrap(), "ax_t ax1 mp");
+    assert_eq!(repl.execute("search (imp _ _)").unwrap(), "thm3\nax1\nmp");
+}
+
+#[test]
+fn step_through_a_theorem() {
+    let mut repl = repl();
+
+    assert_eq!(repl.execute("seek thm3").unwrap(), "at thm3");
+    assert_eq!(
+        repl.execute("stmt").unwrap(),
+        "statement 7: theorem thm3\n  conclusion (imp t t)"
+    );
+
+    // start the statement and the proof, then save the term `t`
+    for _ in 0..3 {
+        repl.execute("step").unwrap();
+    }
+
+    assert_eq!(
+        repl.execute("stack").unwrap(),
+        "proof:\n  0: t\nhyp:\nunify:"
+    );
+
+    let mut last = String::new();
+
+    while !last.ends_with("statement done") {
+        last = repl.execute("step").unwrap();
+    }
+
+    assert_eq!(repl.execute("step").unwrap(), "nothing left to verify");
+    assert_eq!(repl.execute("stmt").unwrap(), "at the end of the file");
+}
+
+#[test]
+fn proof_lists_every_step() {
+    let mut repl = repl();
+    let listing = repl.execute("proof thm3").unwrap();
+
+    assert_eq!(listing.lines().count(), thm3_proof().len());
+    assert!(listing.lines().last().unwrap().contains("|- (imp t t)"));
+}
+
+#[test]
+fn errors() {
+    let mut repl = repl();
+
+    assert_eq!(
+        repl.execute("show thm4"),
+        Err("unknown theorem: thm4".to_string())
+    );
+    assert_eq!(repl.execute("show 4"), Err("no theorem 4".to_string()));
+    assert_eq!(
+        repl.execute("seek"),
+        Err("unknown command: seek, see help".to_string())
+    );
+    assert!(repl.execute("search (imp _").is_err());
+    assert!(repl.execute("help").unwrap().starts_with("commands:"));
+}
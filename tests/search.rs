@@ -0,0 +1,95 @@
+mod common;
+
+use common::{logic, thm3_proof};
+use regex::Regex;
+use trivial_verifier::search::{search, Location, Pattern, PatternError, Query};
+use trivial_verifier::Verifier;
+
+fn verifier() -> Verifier {
+    Verifier::new(&logic(&thm3_proof()).to_bytes()).unwrap()
+}
+
+fn names(verifier: &Verifier, query: &Query) -> Vec<String> {
+    search(verifier, query)
+        .unwrap()
+        .iter()
+        .map(|x| {
+            verifier
+                .get_theorem_name(x.theorem as usize)
+                .unwrap()
+                .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn parse_errors() {
+    let verifier = verifier();
+    let parse = |x| Pattern::parse(x, &verifier);
+
+    assert_eq!(parse("(imp _"), Err(PatternError::UnexpectedEnd));
+    assert_eq!(parse("(imp _ _) t"), Err(PatternError::TrailingInput));
+    assert_eq!(
+        parse("foo"),
+        Err(PatternError::UnknownTerm("foo".to_string()))
+    );
+    assert_eq!(
+        parse("(imp _)"),
+        Err(PatternError::WrongArity {
+            name: "imp".to_string(),
+            expected: 2,
+            found: 1,
+        })
+    );
+}
+
+#[test]
+fn hits_are_ranked_by_location_then_size() {
+    let verifier = verifier();
+    let query = Query {
+        pattern: Some(Pattern::parse("(imp _ _)", &verifier).unwrap()),
+        ..Query::default()
+    };
+
+    let hits = search(&verifier, &query).unwrap();
+    let locations: Vec<_> = hits.iter().map(|x| x.location).collect();
+
+    assert_eq!(names(&verifier, &query), ["thm3", "ax1", "mp"]);
+    assert_eq!(
+        locations,
+        [
+            Some(Location::Conclusion),
+            Some(Location::Conclusion),
+            Some(Location::Hypothesis)
+        ]
+    );
+}
+
+#[test]
+fn nested_pattern_matches_below_the_root() {
+    let verifier = verifier();
+    let query = Query {
+        pattern: Some(Pattern::parse("(imp _ (imp _ _))", &verifier).unwrap()),
+        ..Query::default()
+    };
+
+    assert_eq!(names(&verifier, &query), ["ax1"]);
+}
+
+#[test]
+fn filters_are_combined() {
+    let verifier = verifier();
+
+    let query = Query {
+        mentions: vec![0],
+        ..Query::default()
+    };
+    assert_eq!(names(&verifier, &query), ["ax_t", "thm3"]);
+
+    let query = Query {
+        mentions: vec![0],
+        name: Some(Regex::new("^ax").unwrap()),
+        ..Query::default()
+    };
+    assert_eq!(names(&verifier, &query), ["ax_t"]);
+}
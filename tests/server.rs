@@ -0,0 +1,202 @@
+mod common;
+
+use common::{logic, p, thm3_proof};
+use serde_json::{json, Value};
+use std::io::{Cursor, ErrorKind};
+use std::path::PathBuf;
+use trivial_verifier::kernel::opcode::{Command, Proof};
+use trivial_verifier::server::{self, Server, INVALID_PARAMS, METHOD_NOT_FOUND, NOT_LOADED};
+
+/// Calls the server directly, and keeps the notifications it sent.
+struct Client {
+    server: Server,
+    id: u64,
+    notifications: Vec<Value>,
+}
+
+impl Client {
+    fn new() -> Client {
+        Client {
+            server: Server::new(),
+            id: 0,
+            notifications: Vec::new(),
+        }
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Result<Value, Value> {
+        self.id += 1;
+
+        let request =
+            json!({ "jsonrpc": "2.0", "id": self.id, "method": method, "params": params });
+        let notifications = &mut self.notifications;
+        let response = self
+            .server
+            .handle(&request, &mut |x| notifications.push(x))
+            .expect("a request has a response");
+
+        assert_eq!(response["id"], self.id);
+
+        match response.get("error") {
+            Some(error) => Err(error.clone()),
+            None => Ok(response["result"].clone()),
+        }
+    }
+
+    fn load(&mut self, name: &str, thm3: &[Command<Proof>]) {
+        let path = write(name, &logic(thm3).to_bytes());
+
+        let counts = self.call("load", json!({ "path": path })).unwrap();
+        assert_eq!(counts, json!({ "sorts": 1, "terms": 3, "theorems": 4 }));
+    }
+}
+
+fn write(name: &str, data: &[u8]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("trivial-server-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let path = dir.join(name);
+    std::fs::write(&path, data).unwrap();
+
+    path
+}
+
+#[test]
+fn verify_sends_progress() {
+    let mut client = Client::new();
+    client.load("good.mmb", &thm3_proof());
+
+    let result = client.call("verify", json!({})).unwrap();
+    assert_eq!(result["statements"], 8);
+
+    let last = client.notifications.last().unwrap();
+    assert_eq!(last["method"], "progress");
+    assert_eq!(last["params"], json!({ "done": 8, "total": 8 }));
+}
+
+#[test]
+fn verify_reports_the_failing_statement() {
+    let mut proof = thm3_proof();
+    *proof.last_mut().unwrap() = p(Proof::Thm, 1);
+
+    let mut client = Client::new();
+    client.load("bad.mmb", &proof);
+
+    let error = client.call("verify", json!({})).unwrap_err();
+    assert_eq!(error["data"]["name"], "thm3");
+
+    let last = client.notifications.last().unwrap();
+    assert_eq!(last["method"], "error");
+    assert_eq!(last["params"], error["data"]);
+}
+
+#[test]
+fn step_through_a_theorem() {
+    let mut client = Client::new();
+    client.load("step.mmb", &thm3_proof());
+
+    let seek = client
+        .call("seekTheorem", json!({ "name": "thm3" }))
+        .unwrap();
+    assert_eq!(seek["index"], 3);
+
+    // start the statement and the proof, then save the term `t`
+    for _ in 0..3 {
+        client.call("step", json!({})).unwrap();
+    }
+
+    let stacks = client.call("stacks", json!({})).unwrap();
+    assert_eq!(stacks["proof"], json!([{ "kind": "expr", "expr": "t" }]));
+}
+
+#[test]
+fn queries() {
+    let mut client = Client::new();
+    client.load("queries.mmb", &thm3_proof());
+
+    let statement = client
+        .call("getStatement", json!({ "kind": "theorem", "name": "thm3" }))
+        .unwrap();
+    assert_eq!(statement["name"], "thm3");
+
+    let hits = client.call("search", json!({ "name": "^ax" })).unwrap();
+    let names: Vec<_> = hits
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| &x["name"])
+        .collect();
+    assert_eq!(names, ["ax_t", "ax1"]);
+
+    let deps = client
+        .call("dependencies", json!({ "name": "thm3" }))
+        .unwrap();
+    assert_eq!(deps["terms"], json!(["t", "imp"]));
+    assert_eq!(deps["theorems"], json!(["ax_t", "ax1", "mp"]));
+    assert_eq!(deps["axioms"], json!(["ax_t", "ax1", "mp"]));
+}
+
+#[test]
+fn errors() {
+    let mut client = Client::new();
+
+    let error = client.call("verify", json!({})).unwrap_err();
+    assert_eq!(error["code"], NOT_LOADED);
+
+    let error = client.call("prove", json!({})).unwrap_err();
+    assert_eq!(error["code"], METHOD_NOT_FOUND);
+
+    client.load("errors.mmb", &thm3_proof());
+
+    let error = client
+        .call("seekTheorem", json!({ "name": "thm4" }))
+        .unwrap_err();
+    assert_eq!(error["code"], INVALID_PARAMS);
+}
+
+#[test]
+fn serve_framed_messages() {
+    let path = write("framed.mmb", &logic(&thm3_proof()).to_bytes());
+
+    let mut input = Vec::new();
+    for (id, method, params) in [
+        (1, "load", json!({ "path": path })),
+        (2, "shutdown", json!({})),
+        (3, "verify", json!({})),
+    ]
+    .iter()
+    {
+        let request = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        server::write_message(&mut input, &request).unwrap();
+    }
+
+    let mut output = Vec::new();
+    server::serve(Cursor::new(input), &mut output).unwrap();
+
+    let mut output = Cursor::new(output);
+    let mut responses = Vec::new();
+
+    while let Some(content) = server::read_message(&mut output).unwrap() {
+        responses.push(serde_json::from_slice::<Value>(&content).unwrap());
+    }
+
+    // nothing is answered after the shutdown
+    assert_eq!(responses.len(), 2);
+    assert_eq!(responses[0]["result"]["theorems"], 4);
+    assert_eq!(responses[1]["result"], Value::Null);
+}
+
+#[test]
+fn content_length_is_not_trusted() {
+    let mut input = Cursor::new(&b"Content-Length: 18446744073709551615\r\n\r\n{}"[..]);
+    let error = server::read_message(&mut input).unwrap_err();
+
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+
+    let mut input = Cursor::new(&b"Content-Length: 2\r\n\r\n{}"[..]);
+
+    assert_eq!(
+        server::read_message(&mut input).unwrap(),
+        Some(b"{}".to_vec())
+    );
+    assert_eq!(server::read_message(&mut input).unwrap(), None);
+}
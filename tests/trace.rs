@@ -0,0 +1,90 @@
+mod common;
+
+use common::{logic, thm3_proof, unfinished_thm3_proof};
+use trivial_verifier::expr::Expr;
+use trivial_verifier::kernel::error::Kind;
+use trivial_verifier::trace::{self, Entry};
+use trivial_verifier::Verifier;
+
+fn t() -> Expr {
+    Expr::Term(0, vec![])
+}
+
+fn imp(a: Expr, b: Expr) -> Expr {
+    Expr::Term(1, vec![a, b])
+}
+
+#[test]
+fn steps_record_what_they_use_and_push() {
+    let mut verifier = Verifier::new(&logic(&thm3_proof()).to_bytes()).unwrap();
+    let trace = trace::trace(&mut verifier, 3).unwrap();
+
+    assert_eq!(trace.error, None);
+
+    let steps: Vec<_> = trace
+        .steps
+        .iter()
+        .map(|x| (x.index, x.uses.clone(), x.saved))
+        .collect();
+
+    assert_eq!(
+        steps,
+        [
+            (0, vec![], Some(0)),
+            (1, vec![0], None),
+            (2, vec![0], None),
+            (3, vec![0], None),
+            (4, vec![0], None),
+            (5, vec![0], None),
+            (6, vec![0], None),
+            (7, vec![5, 6], Some(1)),
+            (8, vec![4, 7], None),
+            (9, vec![2, 3, 8], None),
+            (10, vec![0], None),
+            (11, vec![7], None),
+            (12, vec![7], None),
+            (13, vec![1, 9, 10, 11, 12], None),
+        ]
+    );
+
+    let results: Vec<_> = trace.steps.iter().map(|x| x.result.clone()).collect();
+
+    assert_eq!(results[0], Some(Entry::Expr(t())));
+    assert_eq!(results[1], Some(Entry::Proof(t())));
+    // `ref` pushes an entry that is already on the stack
+    assert_eq!(results[3], Some(Entry::Expr(t())));
+    assert_eq!(results[7], Some(Entry::Expr(imp(t(), t()))));
+    assert_eq!(results[9], Some(Entry::Proof(imp(t(), imp(t(), t())))));
+    assert_eq!(results[13], Some(Entry::Proof(imp(t(), t()))));
+}
+
+#[test]
+fn listing_shows_one_step_per_line() {
+    let mut verifier = Verifier::new(&logic(&thm3_proof()).to_bytes()).unwrap();
+    let trace = trace::trace(&mut verifier, 3).unwrap();
+    let names = |x: u32| verifier.get_term_name(x as usize);
+
+    let listing = trace::listing(&verifier, &trace, |x| x.to_display(names).to_string());
+    let lines: Vec<_> = listing.lines().collect();
+
+    assert_eq!(lines.len(), 14);
+    assert_eq!(lines[0], " 0                term t > h0    t");
+    assert_eq!(lines[7], " 7  5,6           term imp > h1  (imp t t)");
+    assert_eq!(lines[13], "13  1,9,10,11,12  thm mp         |- (imp t t)");
+}
+
+#[test]
+fn failing_proof_stops_with_its_error() {
+    let mut verifier = Verifier::new(&logic(&unfinished_thm3_proof()).to_bytes()).unwrap();
+    let trace = trace::trace(&mut verifier, 3).unwrap();
+
+    assert_eq!(trace.steps.len(), 15);
+    assert_eq!(trace.error, Some(Kind::StackHasMoreThanOne));
+    assert_eq!(trace.steps[14].uses, [2, 10, 11, 12, 13]);
+
+    let names = |x: u32| verifier.get_term_name(x as usize);
+    let listing = trace::listing(&verifier, &trace, |x| x.to_display(names).to_string());
+
+    assert!(listing.ends_with("failed: StackHasMoreThanOne\n"));
+    assert_eq!(trace::trace(&mut verifier, 4), None);
+}
@@ -0,0 +1,114 @@
+mod common;
+
+use common::{logic, thm3_proof, unfinished_thm3_proof};
+use ratatui::backend::TestBackend;
+use ratatui::crossterm::event::{KeyCode, KeyEvent};
+use ratatui::Terminal;
+use trivial_verifier::kernel::opcode::{Command, Proof};
+use trivial_verifier::tui::Explorer;
+use trivial_verifier::Verifier;
+
+fn explorer(thm3: &[Command<Proof>]) -> Explorer {
+    Explorer::new(Verifier::new(&logic(thm3).to_bytes()).unwrap())
+}
+
+fn press(explorer: &mut Explorer, code: KeyCode) {
+    explorer.handle_key(KeyEvent::from(code));
+}
+
+fn type_text(explorer: &mut Explorer, text: &str) {
+    for c in text.chars() {
+        press(explorer, KeyCode::Char(c));
+    }
+}
+
+/// Draws the explorer, and returns the lines of the screen.
+fn screen(explorer: &Explorer) -> Vec<String> {
+    let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+    terminal.draw(|frame| explorer.draw(frame)).unwrap();
+
+    let buffer = terminal.backend().buffer();
+
+    (0..buffer.area.height)
+        .map(|y| {
+            (0..buffer.area.width)
+                .map(|x| buffer[(x, y)].symbol())
+                .collect()
+        })
+        .collect()
+}
+
+fn shows(explorer: &Explorer, text: &str) -> bool {
+    screen(explorer).iter().any(|x| x.contains(text))
+}
+
+#[test]
+fn lists_and_filters_theorems() {
+    let mut explorer = explorer(&thm3_proof());
+
+    assert!(shows(&explorer, "theorems 4/4"));
+    assert!(shows(&explorer, "  mp"));
+    assert!(shows(&explorer, "|- t "));
+
+    press(&mut explorer, KeyCode::Down);
+    assert!(shows(&explorer, "|- (imp v0 (imp v1 v0))"));
+
+    // the selection stays on the same theorem while it matches the filter
+    type_text(&mut explorer, "a");
+    assert!(shows(&explorer, "theorems 2/4"));
+    assert!(shows(&explorer, "|- (imp v0 (imp v1 v0))"));
+
+    type_text(&mut explorer, "x_");
+    assert!(shows(&explorer, "theorems 1/4"));
+    assert!(shows(&explorer, "|- t "));
+
+    press(&mut explorer, KeyCode::Backspace);
+    press(&mut explorer, KeyCode::Backspace);
+    press(&mut explorer, KeyCode::Backspace);
+    assert!(shows(&explorer, "theorems 4/4"));
+
+    // the selection does not move past the ends of the list
+    press(&mut explorer, KeyCode::PageDown);
+    assert!(shows(&explorer, "|- (imp t t)"));
+    press(&mut explorer, KeyCode::PageUp);
+    assert!(shows(&explorer, "|- t "));
+}
+
+#[test]
+fn steps_through_a_proof() {
+    let mut explorer = explorer(&thm3_proof());
+
+    type_text(&mut explorer, "thm3");
+    press(&mut explorer, KeyCode::Enter);
+
+    assert!(shows(&explorer, "> thm3"));
+    assert!(shows(&explorer, "started thm3"));
+
+    // start the statement and the proof, then save the term `t`
+    for _ in 0..3 {
+        press(&mut explorer, KeyCode::Right);
+    }
+
+    assert!(shows(&explorer, "step 3"));
+    assert!(shows(&explorer, "0: t "));
+
+    for _ in 0..100 {
+        explorer.step();
+    }
+
+    assert!(shows(&explorer, "thm3 is verified"));
+}
+
+#[test]
+fn shows_a_failing_proof() {
+    let mut explorer = explorer(&unfinished_thm3_proof());
+
+    type_text(&mut explorer, "thm3");
+    press(&mut explorer, KeyCode::Enter);
+
+    for _ in 0..100 {
+        explorer.step();
+    }
+
+    assert!(shows(&explorer, "thm3 fails: StackHasMoreThanOne"));
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::{failing_logic, logic, p, thm3_proof, u, unfinished_thm3_proof, without_index};
+use trivial_verifier::kernel::error::Kind;
+use trivial_verifier::kernel::opcode::{self, Command, Proof, Unify};
+use trivial_verifier::Verifier;
+
+#[test]
+fn file_without_index_has_no_names() {
+    let data = without_index(logic(&thm3_proof()).to_bytes());
+
+    let mut verifier = Verifier::new(&data).expect("the index is optional");
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    assert_eq!(verifier.get_sort_name(0), None);
+    assert_eq!(verifier.get_term_name(0), None);
+    assert_eq!(verifier.get_theorem_name(3), None);
+    assert_eq!(verifier.get_theorem_index("thm3"), None);
+}
+
+fn with_local_theorem(proof: &[Command<Proof>]) -> Vec<u8> {
+    let mut builder = logic(&thm3_proof());
+
+    builder.add_theorem(
+        "thm4",
+        &[],
+        &[u(Unify::Term, 1), u(Unify::Term, 0), u(Unify::Term, 0)],
+        proof,
+        true,
+    );
+
+    builder.to_bytes()
+}
+
+#[test]
+fn local_theorem_is_verified_and_counted() {
+    let data = with_local_theorem(&thm3_proof());
+    let mut verifier = Verifier::new(&data).unwrap();
+
+    let statements = verifier.get_statements();
+    let last = statements.last().unwrap();
+
+    assert_eq!(last.kind, opcode::Statement::LocalTerm);
+    assert_eq!(last.index, 4);
+    assert!(last.is_theorem() && last.is_local());
+
+    assert_eq!(verifier.table.theorems.len(), 5);
+    assert_eq!(verifier.get_theorem_index("thm4"), Some(4));
+    assert!(!verifier.get_public_statements().contains(last));
+
+    verifier.run(&mut |_, _| {}).unwrap();
+
+    let mut proof = thm3_proof();
+    *proof.last_mut().unwrap() = p(Proof::Thm, 1);
+
+    let data = with_local_theorem(&proof);
+    let mut verifier = Verifier::new(&data).unwrap();
+
+    assert!(verifier.run(&mut |_, _| {}).is_err());
+}
+
+#[test]
+fn find_failures_carries_on_after_a_failure() {
+    let mut verifier = Verifier::new(&failing_logic().to_bytes()).unwrap();
+    let failures = verifier.find_failures();
+
+    let theorems: Vec<_> = failures.iter().map(|(x, _)| x.index).collect();
+    assert_eq!(theorems, [3, 5]);
+    assert!(failures
+        .iter()
+        .all(|(_, kind)| *kind == Kind::StackHasMoreThanOne));
+
+    // the proofs are kept after a proof fails
+    assert_eq!(verifier.get_proof(7), Some(&unfinished_thm3_proof()[..]));
+    assert_eq!(verifier.get_proof(8).map(|x| x.len()), Some(4));
+}
+
+#[test]
+fn dependencies_and_axioms() {
+    let verifier = Verifier::new(&failing_logic().to_bytes()).unwrap();
+
+    assert_eq!(verifier.get_dependencies(3), (vec![0, 1], vec![0, 1, 2]));
+    assert_eq!(verifier.get_dependencies(4), (vec![0, 1], vec![3]));
+    assert_eq!(verifier.get_dependencies(0), (vec![0], vec![]));
+
+    // axioms are found through other theorems, but not listed for themselves
+    assert_eq!(verifier.get_axioms(4), [0, 1, 2]);
+    assert!(verifier.get_axioms(1).is_empty());
+}
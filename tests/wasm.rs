@@ -0,0 +1,52 @@
+//! Run with `cargo test --target wasm32-unknown-unknown --features wasm`,
+//! which needs `wasm-bindgen-test-runner` and node.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+mod common;
+
+use common::{logic, thm3_proof, unfinished_thm3_proof};
+use trivial_verifier::wasm::WasmVerifier;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    js_sys::Reflect::get(value, &JsValue::from_str(key)).unwrap()
+}
+
+#[wasm_bindgen_test]
+fn verifies_and_looks_up_names() {
+    let mut verifier = WasmVerifier::new(&logic(&thm3_proof()).to_bytes()).unwrap();
+
+    assert!(verifier.step().unwrap());
+    verifier.run().unwrap();
+    assert!(!verifier.step().unwrap());
+
+    assert_eq!(verifier.sort_name(0).as_deref(), Some("wff"));
+    assert_eq!(verifier.term_index("t"), Some(0));
+    assert_eq!(verifier.theorem_name(3).as_deref(), Some("thm3"));
+}
+
+#[wasm_bindgen_test]
+fn returns_objects() {
+    let verifier = WasmVerifier::new(&logic(&thm3_proof()).to_bytes()).unwrap();
+
+    let statements = verifier.statements().unwrap();
+    assert_eq!(js_sys::Array::from(&statements).length(), 8);
+
+    let theorem = verifier.print_theorem(3, None).unwrap();
+    assert_eq!(get(&theorem, "conclusion"), JsValue::from_str("(imp t t)"));
+
+    let spec = "provable sort wff; term t: wff; term imp (a b: wff): wff;
+        infixr imp: $->$ prec 25; notation t: wff = ($T$:max);";
+    let theorem = verifier.print_theorem(3, Some(spec.to_string())).unwrap();
+    assert_eq!(get(&theorem, "conclusion"), JsValue::from_str("T -> T"));
+}
+
+#[wasm_bindgen_test]
+fn reports_errors() {
+    assert!(WasmVerifier::new(&[0; 8]).is_err());
+
+    let mut verifier = WasmVerifier::new(&logic(&unfinished_thm3_proof()).to_bytes()).unwrap();
+    assert!(verifier.run().is_err());
+}